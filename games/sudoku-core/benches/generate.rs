@@ -0,0 +1,56 @@
+//! Carves the same Hard puzzles with every solver and prints the average time.
+//! Rating is left out, it does not depend on the solver.
+//! Killer layouts, which always use the cage solver, are timed at the end.
+//!
+//! ```
+//! cargo bench -p sudoku-core
+//! ```
+
+use std::time::{Duration, Instant};
+
+use sudoku_core::{
+    generator,
+    solver::{Backtrack, Bitmask, DancingLinks, Solver},
+    Difficulty, PuzzleCode,
+};
+
+const PUZZLES: u32 = 20;
+
+fn bench(name: &str, solver: &dyn Solver) -> Duration {
+    let start = Instant::now();
+    for seed in 0..PUZZLES {
+        let mut rng = PuzzleCode::new(Difficulty::Hard, seed).rng();
+        let puzzle = generator::carve_sudoku(Difficulty::Hard, &mut rng, solver);
+        std::hint::black_box(puzzle);
+    }
+    let average = start.elapsed() / PUZZLES;
+    println!("{:<10} {:>10.2?} per Hard puzzle", name, average);
+    average
+}
+
+fn main() {
+    let backtrack = bench("backtrack", &Backtrack);
+    let bitmask = bench("bitmask", &Bitmask);
+    let dlx = bench("dlx", &DancingLinks);
+    println!(
+        "bitmask is {:.1}x and dlx {:.1}x faster than backtrack",
+        backtrack.as_secs_f64() / bitmask.as_secs_f64(),
+        backtrack.as_secs_f64() / dlx.as_secs_f64()
+    );
+
+    for difficulty in [Difficulty::Hard, Difficulty::None] {
+        let start = Instant::now();
+        let mut slowest = Duration::ZERO;
+        for seed in 0..PUZZLES {
+            let puzzle_start = Instant::now();
+            std::hint::black_box(PuzzleCode::new_killer(difficulty, seed).generate_killer());
+            slowest = slowest.max(puzzle_start.elapsed());
+        }
+        println!(
+            "killer {:<12} {:>10.2?} per puzzle, {:.2?} at most",
+            difficulty.to_string(),
+            start.elapsed() / PUZZLES,
+            slowest
+        );
+    }
+}
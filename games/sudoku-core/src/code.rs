@@ -0,0 +1,120 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{
+    generate_sudoku,
+    generator::generate_sudoku_with,
+    killer::{self, KillerPuzzle},
+    solver::Solver,
+    Difficulty, Puzzle,
+};
+
+/// Short shareable name of a generated puzzle, written like `H-0F3A9C21`.
+/// The letter is the difficulty and the hex digits are the generator seed.
+/// Killer puzzles have a `K` in front, like `KH-0F3A9C21`.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct PuzzleCode {
+    pub difficulty: Difficulty,
+    pub seed: u32,
+    #[serde(default)]
+    pub killer: bool,
+}
+
+impl PuzzleCode {
+    pub fn new(difficulty: Difficulty, seed: u32) -> Self {
+        PuzzleCode {
+            difficulty,
+            seed,
+            killer: false,
+        }
+    }
+
+    pub fn new_killer(difficulty: Difficulty, seed: u32) -> Self {
+        PuzzleCode {
+            killer: true,
+            ..PuzzleCode::new(difficulty, seed)
+        }
+    }
+
+    pub fn random(difficulty: Difficulty) -> Self {
+        PuzzleCode::new(difficulty, rand::thread_rng().gen())
+    }
+
+    /// ChaCha is used because its output is stable across platforms and rand versions
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed as u64)
+    }
+
+    pub fn generate(&self) -> Puzzle {
+        generate_sudoku(self.difficulty, &mut self.rng())
+    }
+
+    pub fn generate_with(&self, solver: &dyn Solver) -> Puzzle {
+        generate_sudoku_with(self.difficulty, &mut self.rng(), solver)
+    }
+
+    /// The cages and solution of a Killer code. Uniqueness is checked with the cage
+    /// solver whatever backend the classic puzzles use.
+    pub fn generate_killer(&self) -> KillerPuzzle {
+        killer::generate(self.difficulty, &mut self.rng())
+    }
+}
+
+impl fmt::Display for PuzzleCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = match self.difficulty {
+            Difficulty::None => 'N',
+            Difficulty::Easy => 'E',
+            Difficulty::Intermediate => 'I',
+            Difficulty::Hard => 'H',
+        };
+        let prefix = if self.killer { "K" } else { "" };
+        write!(f, "{}{}-{:08X}", prefix, letter, self.seed)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParsePuzzleCodeError {
+    UnknownDifficulty,
+    InvalidSeed,
+}
+
+impl fmt::Display for ParsePuzzleCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParsePuzzleCodeError::UnknownDifficulty => write!(f, "unknown difficulty letter"),
+            ParsePuzzleCodeError::InvalidSeed => write!(f, "seed is not 1 to 8 hex digits"),
+        }
+    }
+}
+
+impl Error for ParsePuzzleCodeError {}
+
+impl FromStr for PuzzleCode {
+    type Err = ParsePuzzleCodeError;
+
+    /// Accepts lowercase and a missing dash, so `h0f3a9c21` works too
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let killer = s.starts_with(['K', 'k']);
+        let mut chars = s[usize::from(killer)..].chars();
+        let difficulty = match chars.next().map(|c| c.to_ascii_uppercase()) {
+            Some('N') => Difficulty::None,
+            Some('E') => Difficulty::Easy,
+            Some('I') => Difficulty::Intermediate,
+            Some('H') => Difficulty::Hard,
+            _ => return Err(ParsePuzzleCodeError::UnknownDifficulty),
+        };
+        let seed = chars.as_str().trim_start_matches('-');
+        if seed.is_empty() || seed.len() > 8 || !seed.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParsePuzzleCodeError::InvalidSeed);
+        }
+        let seed = u32::from_str_radix(seed, 16).map_err(|_| ParsePuzzleCodeError::InvalidSeed)?;
+        Ok(PuzzleCode {
+            killer,
+            ..PuzzleCode::new(difficulty, seed)
+        })
+    }
+}
@@ -0,0 +1,98 @@
+//! One shared puzzle per day, the same for every player.
+//!
+//! Days are counted in UTC from 1970-01-01, so a day number works as a date without time zones.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Difficulty, PuzzleCode};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Today's day number
+pub fn today() -> u32 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    (seconds / SECONDS_PER_DAY) as u32
+}
+
+/// Easier early in the week, Hard on weekends
+pub fn difficulty(day: u32) -> Difficulty {
+    // Day 0 was a Thursday, this makes Monday 0
+    match (day + 3) % 7 {
+        0 | 1 => Difficulty::Easy,
+        2..=4 => Difficulty::Intermediate,
+        _ => Difficulty::Hard,
+    }
+}
+
+/// The code of the day's puzzle. The day is scrambled so neighbouring days don't get
+/// neighbouring seeds.
+pub fn code(day: u32) -> PuzzleCode {
+    let mut seed = day.wrapping_mul(0x9E37_79B9);
+    seed ^= seed >> 16;
+    PuzzleCode::new(difficulty(day), seed)
+}
+
+/// The day written as `YYYY-MM-DD`
+pub fn format_date(day: u32) -> String {
+    // Converts days to a civil date, after Howard Hinnant's `civil_from_days`
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// How many days in a row up to `today` appear in `days`. Missing today itself
+/// doesn't break the streak yet, since the day isn't over.
+pub fn streak(days: impl IntoIterator<Item = u32>, today: u32) -> u32 {
+    let mut played: Vec<u32> = days.into_iter().filter(|&day| day <= today).collect();
+    played.sort_unstable();
+    played.dedup();
+    let mut expected = today;
+    if played.last() != Some(&today) {
+        expected = today.saturating_sub(1);
+    }
+    let mut streak = 0;
+    for &day in played.iter().rev() {
+        if day != expected {
+            break;
+        }
+        streak += 1;
+        match expected.checked_sub(1) {
+            Some(previous) => expected = previous,
+            None => break,
+        }
+    }
+    streak
+}
+
+/// The most days in a row found anywhere in `days`
+pub fn longest_streak(days: impl IntoIterator<Item = u32>) -> u32 {
+    let mut played: Vec<u32> = days.into_iter().collect();
+    played.sort_unstable();
+    played.dedup();
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous = None;
+    for day in played {
+        current = match previous {
+            Some(previous) if previous + 1 == day => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+    longest
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Ordered from easiest to hardest
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
+pub enum Difficulty {
+    None,
+    Easy,
+    Intermediate,
+    Hard,
+}
+
+impl Difficulty {
+    /// How many cells the generator tries to empty
+    pub fn cells_to_remove(&self) -> usize {
+        match self {
+            Difficulty::None => 81,
+            Difficulty::Easy => 45,
+            Difficulty::Intermediate => 54,
+            Difficulty::Hard => 63,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::None => write!(f, "None"),
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Intermediate => write!(f, "Intermediate"),
+            Difficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
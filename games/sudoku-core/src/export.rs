@@ -0,0 +1,110 @@
+//! Writing puzzles out for other programs and for print.
+
+use std::fmt::Write;
+
+use crate::{killer::Cage, Grid};
+
+/// Cell size of the printed grid, in SVG user units
+const CELL: usize = 40;
+/// Blank space around the grid
+const MARGIN: usize = 20;
+/// How far cage outlines sit inside the cell borders, in cells
+const CAGE_INSET: f32 = 0.1;
+
+/// 81 characters row by row, `.` for blanks, the same line [`crate::import`] reads
+pub fn to_line(grid: &Grid) -> String {
+    (0..81)
+        .map(|k| match grid[k / 9][k % 9] {
+            0 => '.',
+            number => (b'0' + number) as char,
+        })
+        .collect()
+}
+
+/// A printable grid with thick box borders. Givens are bold black, and cells of `entries`
+/// that are not givens are drawn lighter, so progress or a solution can be shown on top.
+pub fn to_svg(givens: &Grid, entries: Option<&Grid>) -> String {
+    to_svg_with_cages(givens, entries, &[])
+}
+
+/// `to_svg` for Killer puzzles, with each cage outlined in dashes and its sum
+/// in the top left corner
+pub fn to_svg_with_cages(givens: &Grid, entries: Option<&Grid>, cages: &[Cage]) -> String {
+    let size = CELL * 9 + MARGIN * 2;
+    let end = MARGIN + CELL * 9;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+        size
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<rect width="{0}" height="{0}" fill="white"/>"#,
+        size
+    )
+    .unwrap();
+
+    for k in 0..=9 {
+        let position = MARGIN + CELL * k;
+        let width = if k % 3 == 0 { 3 } else { 1 };
+        writeln!(
+            svg,
+            r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="black" stroke-width="{3}" stroke-linecap="square"/>"#,
+            position, MARGIN, end, width
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<line x1="{1}" y1="{0}" x2="{2}" y2="{0}" stroke="black" stroke-width="{3}" stroke-linecap="square"/>"#,
+            position, MARGIN, end, width
+        )
+        .unwrap();
+    }
+
+    let point = |cells: f32| MARGIN as f32 + cells * CELL as f32;
+    for cage in cages {
+        for ((x1, y1), (x2, y2)) in cage.outline(CAGE_INSET) {
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="gray" stroke-width="1" stroke-dasharray="3 3"/>"#,
+                point(x1),
+                point(y1),
+                point(x2),
+                point(y2)
+            )
+            .unwrap();
+        }
+        let (i, j) = cage.label_cell();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="10" fill="black">{}</text>"#,
+            MARGIN + CELL * j + 2,
+            MARGIN + CELL * i + 10,
+            cage.sum
+        )
+        .unwrap();
+    }
+
+    for i in 0..9 {
+        for j in 0..9 {
+            let (number, style) = match (givens[i][j], entries.map(|entries| entries[i][j])) {
+                (0, Some(number)) if number != 0 => (number, r##"fill="#3050a0""##),
+                (0, _) => continue,
+                (number, _) => (number, r#"fill="black" font-weight="bold""#),
+            };
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="26" text-anchor="middle" dominant-baseline="central" {}>{}</text>"#,
+                MARGIN + CELL * j + CELL / 2,
+                MARGIN + CELL * i + CELL / 2,
+                style,
+                number
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
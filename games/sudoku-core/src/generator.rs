@@ -0,0 +1,139 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    logic,
+    solver::{Bitmask, Solver},
+    validator::check_valid,
+    Difficulty, Grid,
+};
+
+/// How many puzzles are carved while looking for one rated at the asked difficulty
+const MAX_ATTEMPTS: usize = 100;
+
+/// A generated puzzle together with the grid it was carved from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Puzzle {
+    pub givens: Grid,
+    pub solution: Grid,
+}
+
+/// Carves puzzles until the logical solver rates one at `difficulty`, see
+/// [`logic::rate`]. If none matches within `MAX_ATTEMPTS` the closest is used.
+/// Every random choice comes from `rng`, so the same seed gives the same puzzle.
+pub fn generate_sudoku<R: Rng>(difficulty: Difficulty, rng: &mut R) -> Puzzle {
+    generate_sudoku_with(difficulty, rng, &Bitmask)
+}
+
+/// Same as [`generate_sudoku`] but checks uniqueness with the given solver.
+/// Every backend counts solutions the same way, so the puzzle does not depend on it.
+pub fn generate_sudoku_with<R: Rng>(
+    difficulty: Difficulty,
+    rng: &mut R,
+    solver: &dyn Solver,
+) -> Puzzle {
+    if difficulty == Difficulty::None {
+        return Puzzle {
+            givens: Grid::new(),
+            solution: random_solution(rng),
+        };
+    }
+
+    let mut closest = None;
+    let mut closest_distance = usize::MAX;
+    for _ in 0..MAX_ATTEMPTS {
+        let puzzle = carve_sudoku(difficulty, rng, solver);
+        let rated = logic::rate(&puzzle.givens).difficulty();
+        if rated == difficulty {
+            return puzzle;
+        }
+        let distance = (rated as usize).abs_diff(difficulty as usize);
+        if distance < closest_distance {
+            closest = Some(puzzle);
+            closest_distance = distance;
+        }
+    }
+    closest.unwrap()
+}
+
+/// Removes up to `difficulty.cells_to_remove()` clues from a random solved grid.
+/// A clue is only removed when the puzzle keeps exactly one solution, so
+/// harder difficulties may end up with a few more clues than asked for.
+pub fn carve_sudoku<R: Rng>(difficulty: Difficulty, rng: &mut R, solver: &dyn Solver) -> Puzzle {
+    let solution = random_solution(rng);
+    let mut givens = solution;
+    let number_remove = difficulty.cells_to_remove();
+    let mut number_removed = 0;
+    let mut cells: Vec<(usize, usize)> = (0..81).map(|k| (k / 9, k % 9)).collect();
+    cells.shuffle(rng);
+
+    for (i, j) in cells {
+        if number_removed == number_remove {
+            break;
+        }
+        let backup = givens[i][j];
+        givens[i][j] = 0;
+        if solver.has_unique_solution(&givens) {
+            number_removed += 1;
+        } else {
+            givens[i][j] = backup;
+        }
+    }
+    Puzzle { givens, solution }
+}
+
+/// Solved grid built from a shuffled backtracking fill, then relabeled and
+/// reordered so that even the fill order leaves no recognizable pattern
+pub fn random_solution<R: Rng>(rng: &mut R) -> Grid {
+    let mut grid = Grid::new();
+    fill_shuffled(&mut grid, rng);
+
+    let mut digits: Vec<u8> = (1..=9).collect();
+    digits.shuffle(rng);
+    let rows = shuffled_lines(rng);
+    let columns = shuffled_lines(rng);
+
+    let mut permuted = Grid::new();
+    for i in 0..9 {
+        for j in 0..9 {
+            permuted[i][j] = digits[grid[rows[i]][columns[j]] as usize - 1];
+        }
+    }
+    permuted
+}
+
+fn fill_shuffled<R: Rng>(grid: &mut Grid, rng: &mut R) -> bool {
+    let (i, j) = if let Some(empty_cell) = grid.find_empty() {
+        empty_cell
+    } else {
+        return true;
+    };
+
+    let mut numbers: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    numbers.shuffle(rng);
+    for number in numbers {
+        if check_valid(number, i, j, grid) {
+            grid[i][j] = number;
+            if fill_shuffled(grid, rng) {
+                return true;
+            }
+            grid[i][j] = 0;
+        }
+    }
+    false
+}
+
+/// Order of rows (or columns) that keeps every region intact: the three bands
+/// are shuffled, then the three lines inside each band
+fn shuffled_lines<R: Rng>(rng: &mut R) -> [usize; 9] {
+    let mut bands = [0, 1, 2];
+    bands.shuffle(rng);
+    let mut lines = [0; 9];
+    for (k, band) in bands.iter().enumerate() {
+        let mut inner = [0, 1, 2];
+        inner.shuffle(rng);
+        for (l, offset) in inner.iter().enumerate() {
+            lines[k * 3 + l] = band * 3 + offset;
+        }
+    }
+    lines
+}
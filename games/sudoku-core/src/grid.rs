@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Index, IndexMut};
+
+/// 9x9 sudoku grid indexed as `grid[row][column]`, 0 means the cell is empty
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub struct Grid {
+    cells: [[u8; 9]; 9],
+}
+
+impl Grid {
+    pub fn new() -> Self {
+        Grid::default()
+    }
+
+    pub fn from_cells(cells: [[u8; 9]; 9]) -> Self {
+        Grid { cells }
+    }
+
+    pub fn cells(&self) -> &[[u8; 9]; 9] {
+        &self.cells
+    }
+
+    /// First empty cell in row-major order
+    pub fn find_empty(&self) -> Option<(usize, usize)> {
+        (0..81)
+            .map(|k| (k / 9, k % 9))
+            .find(|&(i, j)| self.cells[i][j] == 0)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.find_empty().is_none()
+    }
+
+    /// Number of non-empty cells
+    pub fn clue_count(&self) -> usize {
+        self.cells.iter().flatten().filter(|&&n| n != 0).count()
+    }
+}
+
+impl Index<usize> for Grid {
+    type Output = [u8; 9];
+
+    fn index(&self, row: usize) -> &Self::Output {
+        &self.cells[row]
+    }
+}
+
+impl IndexMut<usize> for Grid {
+    fn index_mut(&mut self, row: usize) -> &mut Self::Output {
+        &mut self.cells[row]
+    }
+}
@@ -0,0 +1,180 @@
+//! Reading puzzles written by other programs.
+//!
+//! Three layouts are understood, and may be mixed in one file:
+//! - one puzzle per line as 81 characters, `.` or `0` for blanks (also what `.sdm` files hold)
+//! - the SadMan `.sdk` layout, 9 lines of 9 characters
+//! - blank lines, `#` metadata lines and `[Puzzle]` style section headers, which are skipped
+
+use std::{error::Error, fmt};
+
+use crate::{
+    solver::Solver,
+    validator::{self, PuzzleStatus},
+    Grid, Puzzle,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportError {
+    /// A line is neither a full puzzle nor a row of one
+    WrongLength {
+        line: usize,
+        len: usize,
+    },
+    InvalidCharacter {
+        line: usize,
+        character: char,
+    },
+    /// The file ended in the middle of a 9 line puzzle
+    IncompleteGrid {
+        line: usize,
+        rows: usize,
+    },
+    /// Two givens already break a rule
+    Contradiction {
+        line: usize,
+    },
+    NoPuzzles,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::WrongLength { line, len } => {
+                write!(f, "line {}: expected 9 or 81 cells, found {}", line, len)
+            }
+            ImportError::InvalidCharacter { line, character } => {
+                write!(f, "line {}: unexpected character '{}'", line, character)
+            }
+            ImportError::IncompleteGrid { line, rows } => {
+                write!(f, "line {}: puzzle stops after {} rows", line, rows)
+            }
+            ImportError::Contradiction { line } => {
+                write!(f, "line {}: givens break the rules", line)
+            }
+            ImportError::NoPuzzles => write!(f, "no puzzles found"),
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+/// A puzzle read from a file, checked against the solver
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ImportedPuzzle {
+    /// Line the puzzle starts on, counting from 1
+    pub line: usize,
+    pub givens: Grid,
+    pub status: PuzzleStatus,
+    /// Missing when there is no solution, one of many when there are several
+    pub solution: Option<Grid>,
+}
+
+impl ImportedPuzzle {
+    /// Something the player should know before playing it
+    pub fn warning(&self) -> Option<String> {
+        match self.status {
+            PuzzleStatus::NoSolution => Some(format!("line {}: puzzle has no solution", self.line)),
+            PuzzleStatus::MultipleSolutions => {
+                Some(format!("line {}: puzzle has several solutions", self.line))
+            }
+            _ => None,
+        }
+    }
+
+    /// None when the puzzle can't be finished
+    pub fn puzzle(&self) -> Option<Puzzle> {
+        self.solution.map(|solution| Puzzle {
+            givens: self.givens,
+            solution,
+        })
+    }
+}
+
+/// Reads cells from one line, `.` and `0` being blanks
+fn parse_cells(text: &str, line: usize) -> Result<Vec<u8>, ImportError> {
+    text.chars()
+        .map(|character| match character {
+            '.' | '0' => Ok(0),
+            '1'..='9' => Ok(character as u8 - b'0'),
+            _ => Err(ImportError::InvalidCharacter { line, character }),
+        })
+        .collect()
+}
+
+/// Reads a single puzzle written as 81 characters
+pub fn parse_line(text: &str) -> Result<Grid, ImportError> {
+    let cells = parse_cells(text.trim(), 1)?;
+    if cells.len() != 81 {
+        return Err(ImportError::WrongLength {
+            line: 1,
+            len: cells.len(),
+        });
+    }
+    Ok(grid_from_cells(&cells))
+}
+
+fn grid_from_cells(cells: &[u8]) -> Grid {
+    let mut grid = Grid::new();
+    for (k, &number) in cells.iter().enumerate() {
+        grid[k / 9][k % 9] = number;
+    }
+    grid
+}
+
+/// Reads every puzzle in `text`. Malformed lines and contradictory givens fail the
+/// whole import, while puzzles without exactly one solution only carry a warning.
+pub fn import_puzzles(text: &str, solver: &dyn Solver) -> Result<Vec<ImportedPuzzle>, ImportError> {
+    let mut grids = Vec::new();
+    // Rows of a 9 line puzzle read so far, with the line it started on
+    let mut rows = Vec::new();
+    let mut start = 0;
+    for (index, text) in text.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') || text.starts_with('[') {
+            continue;
+        }
+        let cells = parse_cells(text, line)?;
+        match cells.len() {
+            81 if rows.is_empty() => grids.push((line, grid_from_cells(&cells))),
+            9 => {
+                if rows.is_empty() {
+                    start = line;
+                }
+                rows.extend(cells);
+                if rows.len() == 81 {
+                    grids.push((start, grid_from_cells(&rows)));
+                    rows.clear();
+                }
+            }
+            len => return Err(ImportError::WrongLength { line, len }),
+        }
+    }
+    if !rows.is_empty() {
+        return Err(ImportError::IncompleteGrid {
+            line: start,
+            rows: rows.len() / 9,
+        });
+    }
+    if grids.is_empty() {
+        return Err(ImportError::NoPuzzles);
+    }
+
+    grids
+        .into_iter()
+        .map(|(line, givens)| {
+            let status = validator::check_puzzle(&givens, solver);
+            if status == PuzzleStatus::Contradiction {
+                return Err(ImportError::Contradiction { line });
+            }
+            let mut solution = givens;
+            let solved = status != PuzzleStatus::NoSolution && solver.solve(&mut solution);
+            Ok(ImportedPuzzle {
+                line,
+                givens,
+                status,
+                solution: solved.then_some(solution),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,236 @@
+//! Killer sudoku: on top of the usual rules the grid is split into cages, and the
+//! digits of a cage add up to its sum without repeating.
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{generator, solver::killer, Difficulty, Grid, Puzzle};
+
+/// Cells that add up to `sum`, each at most once per digit
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Cage {
+    pub cells: Vec<(usize, usize)>,
+    pub sum: u8,
+}
+
+impl Cage {
+    /// Cage over `cells` with the sum they have in `solution`
+    pub fn from_solution(cells: Vec<(usize, usize)>, solution: &Grid) -> Self {
+        let sum = cells.iter().map(|&(i, j)| solution[i][j]).sum();
+        Cage { cells, sum }
+    }
+
+    /// The first cell in reading order, where the sum is written
+    pub fn label_cell(&self) -> (usize, usize) {
+        *self.cells.iter().min().unwrap()
+    }
+
+    /// Sides of the outline as end points `(x, y)` measured in cells from the top left
+    /// of the grid. The outline sits `inset` cells inside the cage so neighbouring cages
+    /// stay apart, and a side runs on into the next cell when the outline does.
+    pub fn outline(&self, inset: f32) -> Vec<((f32, f32), (f32, f32))> {
+        let in_cage = |i: isize, j: isize| {
+            self.cells
+                .iter()
+                .any(|&(ci, cj)| ci as isize == i && cj as isize == j)
+        };
+        let mut sides = Vec::new();
+        for &(i, j) in self.cells.iter() {
+            let center = (j as f32 + 0.5, i as f32 + 0.5);
+            let (i, j) = (i as isize, j as isize);
+            for (di, dj) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                if in_cage(i + di, j + dj) {
+                    continue;
+                }
+                let middle = (
+                    center.0 + dj as f32 * (0.5 - inset),
+                    center.1 + di as f32 * (0.5 - inset),
+                );
+                // How far the side reaches towards the cell at `step` along it
+                let reach = |step: isize| {
+                    let (ai, aj) = (i + dj * step, j + di * step);
+                    if !in_cage(ai, aj) {
+                        0.5 - inset
+                    } else if !in_cage(ai + di, aj + dj) {
+                        0.5
+                    } else {
+                        // Inner corner, meets the side of the cage cell across it
+                        0.5 + inset
+                    }
+                };
+                let (before, after) = (reach(-1), reach(1));
+                sides.push((
+                    (middle.0 - di as f32 * before, middle.1 - dj as f32 * before),
+                    (middle.0 + di as f32 * after, middle.1 + dj as f32 * after),
+                ));
+            }
+        }
+        sides
+    }
+}
+
+/// A generated Killer puzzle. Every cell is in exactly one cage.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KillerPuzzle {
+    pub cages: Vec<Cage>,
+    pub puzzle: Puzzle,
+}
+
+/// Digits the uniqueness check may try on one layout before it is made easier to check.
+/// Generation runs while the game waits, this keeps the slowest layouts well under a
+/// second even in a debug build, at the cost of a few more cages.
+const MAX_SEARCH_STEPS: usize = 2_000;
+
+/// Largest cage the generator grows, bigger cages make for a harder puzzle.
+/// None is free play in the classic game and isn't offered for Killer, a `KN` code
+/// still makes the largest cages of all.
+pub fn max_cage_size(difficulty: Difficulty) -> usize {
+    match difficulty {
+        Difficulty::Easy => 3,
+        Difficulty::Intermediate => 4,
+        Difficulty::Hard => 5,
+        Difficulty::None => 6,
+    }
+}
+
+/// Lays random cages over a random solution, then splits cages until the cage sums
+/// alone leave one solution. There are no givens.
+/// Every random choice comes from `rng`, so the same seed gives the same puzzle.
+pub fn generate<R: Rng>(difficulty: Difficulty, rng: &mut R) -> KillerPuzzle {
+    let solution = generator::random_solution(rng);
+    let givens = Grid::new();
+    let mut cages = random_cages(max_cage_size(difficulty), &solution, rng);
+    // Splitting only adds rules, and with every cell in its own cage the grid is given
+    // away, so this always ends
+    loop {
+        let (i, j) = match killer::solutions_within(&givens, &cages, 2, MAX_SEARCH_STEPS) {
+            Some(solutions) => {
+                let Some(other) = solutions.into_iter().find(|other| *other != solution) else {
+                    break;
+                };
+                let differing: Vec<(usize, usize)> = (0..81)
+                    .map(|k| (k / 9, k % 9))
+                    .filter(|&(i, j)| other[i][j] != solution[i][j])
+                    .collect();
+                *differing.choose(rng).unwrap()
+            }
+            // Too slow to tell, a smaller cage narrows the search
+            None => {
+                let largest = cages.iter().map(|cage| cage.cells.len()).max().unwrap();
+                let candidates: Vec<&Cage> = cages
+                    .iter()
+                    .filter(|cage| cage.cells.len() == largest)
+                    .collect();
+                *candidates.choose(rng).unwrap().cells.choose(rng).unwrap()
+            }
+        };
+        let index = cages
+            .iter()
+            .position(|cage| cage.cells.contains(&(i, j)))
+            .unwrap();
+        let cage = cages.swap_remove(index);
+        cages.extend(split_cage(cage, (i, j), &solution));
+    }
+    cages.sort_by_key(|cage| cage.label_cell());
+    KillerPuzzle {
+        cages,
+        puzzle: Puzzle { givens, solution },
+    }
+}
+
+fn neighbours(i: usize, j: usize) -> impl Iterator<Item = (usize, usize)> {
+    [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        .into_iter()
+        .filter_map(move |(di, dj)| Some((i.checked_add_signed(di)?, j.checked_add_signed(dj)?)))
+        .filter(|&(i, j)| i < 9 && j < 9)
+}
+
+/// Grows cages of up to `max_size` connected cells from random starting cells,
+/// never taking a cell whose digit the cage already has
+fn random_cages<R: Rng>(max_size: usize, solution: &Grid, rng: &mut R) -> Vec<Cage> {
+    let mut taken = [[false; 9]; 9];
+    let mut starts: Vec<(usize, usize)> = (0..81).map(|k| (k / 9, k % 9)).collect();
+    starts.shuffle(rng);
+    let mut cages = Vec::new();
+    for (i, j) in starts {
+        if taken[i][j] {
+            continue;
+        }
+        let size = rng.gen_range(2..=max_size);
+        let mut cells = vec![(i, j)];
+        taken[i][j] = true;
+        while cells.len() < size {
+            let mut options: Vec<(usize, usize)> = cells
+                .iter()
+                .flat_map(|&(i, j)| neighbours(i, j))
+                .filter(|&(i, j)| {
+                    !taken[i][j]
+                        && cells
+                            .iter()
+                            .all(|&(ci, cj)| solution[ci][cj] != solution[i][j])
+                })
+                .collect();
+            options.sort_unstable();
+            options.dedup();
+            let Some(&(i, j)) = options.choose(rng) else {
+                break;
+            };
+            taken[i][j] = true;
+            cells.push((i, j));
+        }
+        cells.sort_unstable();
+        cages.push(Cage::from_solution(cells, solution));
+    }
+    cages
+}
+
+/// Breaks a cage into connected pieces: about half of it around `cell`, and the rest
+fn split_cage(cage: Cage, cell: (usize, usize), solution: &Grid) -> Vec<Cage> {
+    if cage.cells.len() == 1 {
+        return vec![cage];
+    }
+    let mut first = vec![cell];
+    let mut frontier = vec![cell];
+    while first.len() < cage.cells.len() / 2 {
+        let Some((i, j)) = frontier.pop() else {
+            break;
+        };
+        for next in neighbours(i, j) {
+            if first.len() < cage.cells.len() / 2
+                && cage.cells.contains(&next)
+                && !first.contains(&next)
+            {
+                first.push(next);
+                frontier.insert(0, next);
+            }
+        }
+    }
+    let mut pieces = vec![first];
+    let mut rest: Vec<(usize, usize)> = cage
+        .cells
+        .into_iter()
+        .filter(|cell| !pieces[0].contains(cell))
+        .collect();
+    // What is left may fall apart, each connected part becomes a cage
+    while let Some(start) = rest.pop() {
+        let mut piece = vec![start];
+        let mut k = 0;
+        while k < piece.len() {
+            let (i, j) = piece[k];
+            for next in neighbours(i, j) {
+                if let Some(position) = rest.iter().position(|&cell| cell == next) {
+                    piece.push(rest.swap_remove(position));
+                }
+            }
+            k += 1;
+        }
+        pieces.push(piece);
+    }
+    pieces
+        .into_iter()
+        .map(|mut cells| {
+            cells.sort_unstable();
+            Cage::from_solution(cells, solution)
+        })
+        .collect()
+}
@@ -0,0 +1,20 @@
+//! Sudoku puzzle logic without any rendering.
+//!
+//! The game crate draws from these types, while tools and tests can use them directly.
+
+mod code;
+pub mod daily;
+mod difficulty;
+pub mod export;
+pub mod generator;
+mod grid;
+pub mod import;
+pub mod killer;
+pub mod logic;
+pub mod solver;
+pub mod validator;
+
+pub use code::{ParsePuzzleCodeError, PuzzleCode};
+pub use difficulty::Difficulty;
+pub use generator::{generate_sudoku, Puzzle};
+pub use grid::Grid;
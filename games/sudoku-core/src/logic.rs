@@ -0,0 +1,580 @@
+//! Solving the way a person does, one deduction at a time. Every [`Step`]
+//! names the [`Technique`] behind it, which is what puzzles are graded by.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{Difficulty, Grid};
+
+const ALL_DIGITS: u16 = 0x1FF;
+
+fn bit(number: u8) -> u16 {
+    1 << (number - 1)
+}
+
+fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (1..=9).filter(move |&n| mask & bit(n) != 0)
+}
+
+/// Every way to pick `k` items out of `items`, in order
+fn combinations<T: Copy>(items: &[T], k: usize) -> Vec<Vec<T>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (index, &item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[index + 1..], k - 1) {
+            rest.insert(0, item);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+fn cell_name((i, j): (usize, usize)) -> String {
+    format!("r{}c{}", i + 1, j + 1)
+}
+
+/// "a", "a and b", "a, b and c"
+fn join(items: Vec<String>) -> String {
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => String::new(),
+    }
+}
+
+/// Ordered from easiest to hardest
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    NakedPair,
+    HiddenPair,
+    PointingPair,
+    BoxLineReduction,
+    NakedTriple,
+    HiddenTriple,
+    XWing,
+    Swordfish,
+}
+
+impl Technique {
+    /// Difficulty of a puzzle whose hardest step uses this technique
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::XWing | Technique::Swordfish => Difficulty::Hard,
+            _ => Difficulty::Intermediate,
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Technique::NakedSingle => "Naked single",
+            Technique::HiddenSingle => "Hidden single",
+            Technique::NakedPair => "Naked pair",
+            Technique::HiddenPair => "Hidden pair",
+            Technique::PointingPair => "Pointing pair",
+            Technique::BoxLineReduction => "Box-line reduction",
+            Technique::NakedTriple => "Naked triple",
+            Technique::HiddenTriple => "Hidden triple",
+            Technique::XWing => "X-Wing",
+            Technique::Swordfish => "Swordfish",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A row, column or region (box), numbered from 0
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Region(usize),
+}
+
+impl Unit {
+    pub fn all() -> impl Iterator<Item = Unit> {
+        (0..9)
+            .map(Unit::Row)
+            .chain((0..9).map(Unit::Column))
+            .chain((0..9).map(Unit::Region))
+    }
+
+    pub fn cells(&self) -> [(usize, usize); 9] {
+        let mut cells = [(0, 0); 9];
+        for (k, cell) in cells.iter_mut().enumerate() {
+            *cell = match *self {
+                Unit::Row(i) => (i, k),
+                Unit::Column(j) => (k, j),
+                Unit::Region(r) => (r / 3 * 3 + k / 3, r % 3 * 3 + k % 3),
+            };
+        }
+        cells
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unit::Row(i) => write!(f, "row {}", i + 1),
+            Unit::Column(j) => write!(f, "column {}", j + 1),
+            Unit::Region(r) => write!(f, "box {}", r + 1),
+        }
+    }
+}
+
+/// One deduction, either placing a digit or ruling out candidates
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub technique: Technique,
+    /// `(row, column, number)` placed by a single
+    pub placement: Option<(usize, usize, u8)>,
+    /// `(row, column, number)` candidates that can be crossed out
+    pub eliminations: Vec<(usize, usize, u8)>,
+    /// Cells the deduction is based on
+    pub cells: Vec<(usize, usize)>,
+    pub explanation: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Rating {
+    /// Hardest technique needed, None when there was nothing to deduce
+    pub hardest: Option<Technique>,
+    /// false when the known techniques were not enough to finish the grid
+    pub solved: bool,
+}
+
+impl Rating {
+    /// Puzzles that need more than the known techniques count as Hard
+    pub fn difficulty(&self) -> Difficulty {
+        if !self.solved {
+            return Difficulty::Hard;
+        }
+        self.hardest
+            .map_or(Difficulty::Easy, |technique| technique.difficulty())
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.hardest, self.solved) {
+            (_, false) => write!(f, "Beyond Swordfish"),
+            (Some(technique), true) => write!(f, "{}", technique),
+            (None, true) => write!(f, "Solved"),
+        }
+    }
+}
+
+/// Grid plus the candidates left in every empty cell
+#[derive(Clone, Debug)]
+pub struct LogicSolver {
+    grid: Grid,
+    candidates: [[u16; 9]; 9],
+}
+
+impl LogicSolver {
+    pub fn new(grid: &Grid) -> Self {
+        let mut solver = LogicSolver {
+            grid: *grid,
+            candidates: [[0; 9]; 9],
+        };
+        for i in 0..9 {
+            for j in 0..9 {
+                if grid[i][j] == 0 {
+                    solver.candidates[i][j] = ALL_DIGITS & !solver.seen(i, j);
+                }
+            }
+        }
+        solver
+    }
+
+    /// Digits already placed in the row, column or region of the cell
+    fn seen(&self, i: usize, j: usize) -> u16 {
+        let region = i / 3 * 3 + j / 3;
+        [Unit::Row(i), Unit::Column(j), Unit::Region(region)]
+            .iter()
+            .flat_map(|unit| unit.cells())
+            .filter(|&(a, b)| self.grid[a][b] != 0)
+            .fold(0, |mask, (a, b)| mask | bit(self.grid[a][b]))
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Candidates of the cell as a bitmask, bit `n - 1` for digit `n`
+    pub fn candidates(&self, i: usize, j: usize) -> u16 {
+        self.candidates[i][j]
+    }
+
+    pub fn place(&mut self, i: usize, j: usize, number: u8) {
+        self.grid[i][j] = number;
+        self.candidates[i][j] = 0;
+        let region = i / 3 * 3 + j / 3;
+        for unit in [Unit::Row(i), Unit::Column(j), Unit::Region(region)] {
+            for (a, b) in unit.cells() {
+                self.candidates[a][b] &= !bit(number);
+            }
+        }
+    }
+
+    pub fn eliminate(&mut self, i: usize, j: usize, number: u8) {
+        self.candidates[i][j] &= !bit(number);
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        if let Some((i, j, number)) = step.placement {
+            self.place(i, j, number);
+        }
+        for &(i, j, number) in step.eliminations.iter() {
+            self.eliminate(i, j, number);
+        }
+    }
+
+    /// The easiest deduction available, None when stuck or solved
+    pub fn next_step(&self) -> Option<Step> {
+        self.naked_single()
+            .or_else(|| self.hidden_single())
+            .or_else(|| self.naked_subset(2))
+            .or_else(|| self.hidden_subset(2))
+            .or_else(|| self.pointing())
+            .or_else(|| self.box_line_reduction())
+            .or_else(|| self.naked_subset(3))
+            .or_else(|| self.hidden_subset(3))
+            .or_else(|| self.fish(2))
+            .or_else(|| self.fish(3))
+    }
+
+    /// Applies steps until stuck and rates what it took
+    pub fn solve(&mut self) -> Rating {
+        let mut hardest = None;
+        while let Some(step) = self.next_step() {
+            hardest = hardest.max(Some(step.technique));
+            self.apply(&step);
+        }
+        Rating {
+            hardest,
+            solved: self.grid.is_filled(),
+        }
+    }
+
+    /// Cells of the unit where `number` is still a candidate
+    fn positions(&self, unit: Unit, number: u8) -> Vec<(usize, usize)> {
+        unit.cells()
+            .into_iter()
+            .filter(|&(i, j)| self.candidates[i][j] & bit(number) != 0)
+            .collect()
+    }
+
+    fn naked_single(&self) -> Option<Step> {
+        for i in 0..9 {
+            for j in 0..9 {
+                let candidates = self.candidates[i][j];
+                if self.grid[i][j] == 0 && candidates.count_ones() == 1 {
+                    let number = candidates.trailing_zeros() as u8 + 1;
+                    return Some(Step {
+                        technique: Technique::NakedSingle,
+                        placement: Some((i, j, number)),
+                        eliminations: Vec::new(),
+                        cells: vec![(i, j)],
+                        explanation: format!(
+                            "Naked single: {} can only be {}",
+                            cell_name((i, j)),
+                            number
+                        ),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn hidden_single(&self) -> Option<Step> {
+        for unit in Unit::all() {
+            for number in 1..=9 {
+                let positions = self.positions(unit, number);
+                if let [(i, j)] = positions[..] {
+                    return Some(Step {
+                        technique: Technique::HiddenSingle,
+                        placement: Some((i, j, number)),
+                        eliminations: Vec::new(),
+                        cells: unit.cells().to_vec(),
+                        explanation: format!(
+                            "Hidden single: {} can only go in {} within {}",
+                            number,
+                            cell_name((i, j)),
+                            unit
+                        ),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// `size` cells of a unit that share exactly `size` candidates
+    fn naked_subset(&self, size: usize) -> Option<Step> {
+        let technique = if size == 2 {
+            Technique::NakedPair
+        } else {
+            Technique::NakedTriple
+        };
+        for unit in Unit::all() {
+            let empty: Vec<(usize, usize)> = unit
+                .cells()
+                .into_iter()
+                .filter(|&(i, j)| {
+                    let count = self.candidates[i][j].count_ones() as usize;
+                    self.grid[i][j] == 0 && count >= 2 && count <= size
+                })
+                .collect();
+            for subset in combinations(&empty, size) {
+                let union = subset
+                    .iter()
+                    .fold(0, |mask, &(i, j)| mask | self.candidates[i][j]);
+                if union.count_ones() as usize != size {
+                    continue;
+                }
+                let eliminations: Vec<(usize, usize, u8)> = unit
+                    .cells()
+                    .into_iter()
+                    .filter(|cell| !subset.contains(cell))
+                    .flat_map(|(i, j)| {
+                        digits(self.candidates[i][j] & union).map(move |n| (i, j, n))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique,
+                        placement: None,
+                        eliminations,
+                        explanation: format!(
+                            "{}: {} can only be {}, so remove them from the rest of {}",
+                            technique,
+                            join(subset.iter().map(|&cell| cell_name(cell)).collect()),
+                            join(digits(union).map(|n| n.to_string()).collect()),
+                            unit
+                        ),
+                        cells: subset,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// `size` digits of a unit that only fit in the same `size` cells
+    fn hidden_subset(&self, size: usize) -> Option<Step> {
+        let technique = if size == 2 {
+            Technique::HiddenPair
+        } else {
+            Technique::HiddenTriple
+        };
+        for unit in Unit::all() {
+            let numbers: Vec<u8> = (1..=9)
+                .filter(|&n| {
+                    let count = self.positions(unit, n).len();
+                    count >= 2 && count <= size
+                })
+                .collect();
+            for subset in combinations(&numbers, size) {
+                let mut cells: Vec<(usize, usize)> = Vec::new();
+                for &n in subset.iter() {
+                    for cell in self.positions(unit, n) {
+                        if !cells.contains(&cell) {
+                            cells.push(cell);
+                        }
+                    }
+                }
+                if cells.len() != size {
+                    continue;
+                }
+                let keep = subset.iter().fold(0, |mask, &n| mask | bit(n));
+                let eliminations: Vec<(usize, usize, u8)> = cells
+                    .iter()
+                    .flat_map(|&(i, j)| {
+                        digits(self.candidates[i][j] & !keep).map(move |n| (i, j, n))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    cells.sort();
+                    return Some(Step {
+                        technique,
+                        placement: None,
+                        eliminations,
+                        explanation: format!(
+                            "{}: {} only fit in {} within {}, so remove the other candidates there",
+                            technique,
+                            join(subset.iter().map(|n| n.to_string()).collect()),
+                            join(cells.iter().map(|&cell| cell_name(cell)).collect()),
+                            unit
+                        ),
+                        cells,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// A digit that only fits on one line inside a region is removed from the rest of that line
+    fn pointing(&self) -> Option<Step> {
+        for region in 0..9 {
+            for number in 1..=9 {
+                let positions = self.positions(Unit::Region(region), number);
+                if positions.len() < 2 {
+                    continue;
+                }
+                let line = if positions.iter().all(|&(i, _)| i == positions[0].0) {
+                    Unit::Row(positions[0].0)
+                } else if positions.iter().all(|&(_, j)| j == positions[0].1) {
+                    Unit::Column(positions[0].1)
+                } else {
+                    continue;
+                };
+                let eliminations: Vec<(usize, usize, u8)> = self
+                    .positions(line, number)
+                    .into_iter()
+                    .filter(|&(i, j)| i / 3 * 3 + j / 3 != region)
+                    .map(|(i, j)| (i, j, number))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::PointingPair,
+                        placement: None,
+                        eliminations,
+                        explanation: format!(
+                            "Pointing pair: in {} {} only fits in {}, so remove it from the rest of {}",
+                            Unit::Region(region),
+                            number,
+                            line,
+                            line
+                        ),
+                        cells: positions,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// A digit that only fits in one region along a line is removed from the rest of that region
+    fn box_line_reduction(&self) -> Option<Step> {
+        for line in (0..9).map(Unit::Row).chain((0..9).map(Unit::Column)) {
+            for number in 1..=9 {
+                let positions = self.positions(line, number);
+                if positions.len() < 2 {
+                    continue;
+                }
+                let region_of = |(i, j): (usize, usize)| i / 3 * 3 + j / 3;
+                let region = region_of(positions[0]);
+                if !positions.iter().all(|&cell| region_of(cell) == region) {
+                    continue;
+                }
+                let eliminations: Vec<(usize, usize, u8)> = self
+                    .positions(Unit::Region(region), number)
+                    .into_iter()
+                    .filter(|cell| !positions.contains(cell))
+                    .map(|(i, j)| (i, j, number))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::BoxLineReduction,
+                        placement: None,
+                        eliminations,
+                        explanation: format!(
+                            "Box-line reduction: in {} {} only fits in {}, so remove it from the rest of {}",
+                            line,
+                            number,
+                            Unit::Region(region),
+                            Unit::Region(region)
+                        ),
+                        cells: positions,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// X-Wing for `size` 2, Swordfish for 3: `size` lines where a digit only
+    /// fits in the same `size` cross lines clear it from the rest of those cross lines
+    fn fish(&self, size: usize) -> Option<Step> {
+        let technique = if size == 2 {
+            Technique::XWing
+        } else {
+            Technique::Swordfish
+        };
+        for by_rows in [true, false] {
+            let line = |k: usize| {
+                if by_rows {
+                    Unit::Row(k)
+                } else {
+                    Unit::Column(k)
+                }
+            };
+            let cross = |k: usize| {
+                if by_rows {
+                    Unit::Column(k)
+                } else {
+                    Unit::Row(k)
+                }
+            };
+            let cross_index = |(i, j): (usize, usize)| if by_rows { j } else { i };
+            for number in 1..=9 {
+                let bases: Vec<usize> = (0..9)
+                    .filter(|&k| {
+                        let count = self.positions(line(k), number).len();
+                        count >= 2 && count <= size
+                    })
+                    .collect();
+                for subset in combinations(&bases, size) {
+                    let mut cells = Vec::new();
+                    let mut covers = 0u16;
+                    for &k in subset.iter() {
+                        for cell in self.positions(line(k), number) {
+                            covers |= 1 << cross_index(cell);
+                            cells.push(cell);
+                        }
+                    }
+                    if covers.count_ones() as usize != size {
+                        continue;
+                    }
+                    let cross_lines: Vec<usize> =
+                        (0..9).filter(|k| covers & (1 << k) != 0).collect();
+                    let eliminations: Vec<(usize, usize, u8)> = cross_lines
+                        .iter()
+                        .flat_map(|&k| self.positions(cross(k), number))
+                        .filter(|cell| !cells.contains(cell))
+                        .map(|(i, j)| (i, j, number))
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique,
+                            placement: None,
+                            eliminations,
+                            explanation: format!(
+                                "{}: {} in {} only fits in {}, so remove it from the rest of them",
+                                technique,
+                                number,
+                                join(subset.iter().map(|&k| line(k).to_string()).collect()),
+                                join(cross_lines.iter().map(|&k| cross(k).to_string()).collect())
+                            ),
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Rates a puzzle by the hardest technique needed to solve it
+pub fn rate(grid: &Grid) -> Rating {
+    LogicSolver::new(grid).solve()
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Grid;
+
+pub mod backtrack;
+pub mod bitmask;
+pub mod dlx;
+pub mod killer;
+
+pub use bitmask::{count_solutions, solve_sudoku};
+
+pub fn has_unique_solution(grid: &Grid) -> bool {
+    count_solutions(grid, 2) == 1
+}
+
+/// Common interface of the solver backends, so the generator and the
+/// validator can run on any of them
+pub trait Solver {
+    /// Fills every empty cell, returns false when there is no solution
+    fn solve(&self, grid: &mut Grid) -> bool;
+
+    /// Counts the solutions of the grid, stops searching once `limit` solutions are found
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize;
+
+    fn has_unique_solution(&self, grid: &Grid) -> bool {
+        self.count_solutions(grid, 2) == 1
+    }
+}
+
+/// Naive backtracking, see [`backtrack`]
+pub struct Backtrack;
+
+/// Candidate bitmasks with most-constrained cell first, see [`bitmask`]
+pub struct Bitmask;
+
+/// Algorithm X on dancing links, see [`dlx`]
+pub struct DancingLinks;
+
+impl Solver for Backtrack {
+    fn solve(&self, grid: &mut Grid) -> bool {
+        backtrack::solve_sudoku(grid)
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        backtrack::count_solutions(grid, limit)
+    }
+}
+
+impl Solver for Bitmask {
+    fn solve(&self, grid: &mut Grid) -> bool {
+        bitmask::solve_sudoku(grid)
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        bitmask::count_solutions(grid, limit)
+    }
+}
+
+impl Solver for DancingLinks {
+    fn solve(&self, grid: &mut Grid) -> bool {
+        dlx::solve_sudoku(grid)
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        dlx::count_solutions(grid, limit)
+    }
+}
+
+/// Backend picked at runtime, for example from a setting
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum SolverKind {
+    Backtrack,
+    #[default]
+    Bitmask,
+    DancingLinks,
+}
+
+impl Solver for SolverKind {
+    fn solve(&self, grid: &mut Grid) -> bool {
+        match self {
+            SolverKind::Backtrack => Backtrack.solve(grid),
+            SolverKind::Bitmask => Bitmask.solve(grid),
+            SolverKind::DancingLinks => DancingLinks.solve(grid),
+        }
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        match self {
+            SolverKind::Backtrack => Backtrack.count_solutions(grid, limit),
+            SolverKind::Bitmask => Bitmask.count_solutions(grid, limit),
+            SolverKind::DancingLinks => DancingLinks.count_solutions(grid, limit),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! The original solver: scans for the first empty cell and rechecks the row,
+//! column and region for every digit. Kept as a reference for tests and benchmarks.
+
+use crate::{
+    validator::{check_valid, is_consistent},
+    Grid,
+};
+
+/// Fills every empty cell with backtracking, returns false when there is no solution
+pub fn solve_sudoku(grid: &mut Grid) -> bool {
+    // A broken grid would otherwise be searched to the end before giving up
+    if !is_consistent(grid) {
+        return false;
+    }
+    solve_from(grid)
+}
+
+fn solve_from(grid: &mut Grid) -> bool {
+    let (i, j) = if let Some(empty_cell) = grid.find_empty() {
+        empty_cell
+    } else {
+        return true;
+    };
+
+    for number in 1..=9 {
+        if check_valid(number, i, j, grid) {
+            grid[i][j] = number;
+            if solve_from(grid) {
+                return true;
+            }
+            grid[i][j] = 0;
+        }
+    }
+    false
+}
+
+/// Counts the solutions of the grid, stops searching once `limit` solutions are found
+pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+    if !is_consistent(grid) {
+        return 0;
+    }
+    let mut grid = *grid;
+    let mut count = 0;
+    count_solutions_from(&mut grid, limit, &mut count);
+    count
+}
+
+fn count_solutions_from(grid: &mut Grid, limit: usize, count: &mut usize) {
+    let (i, j) = if let Some(empty_cell) = grid.find_empty() {
+        empty_cell
+    } else {
+        *count += 1;
+        return;
+    };
+
+    for number in 1..=9 {
+        if *count >= limit {
+            break;
+        }
+        if check_valid(number, i, j, grid) {
+            grid[i][j] = number;
+            count_solutions_from(grid, limit, count);
+            grid[i][j] = 0;
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Backtracking over candidate bitmasks. Every row, column and region keeps a
+//! `u16` of the digits it already holds (bit `n - 1` for digit `n`), so the
+//! candidates of a cell are three ORs away, and the search always branches on
+//! the empty cell with the fewest candidates.
+
+use crate::Grid;
+
+const ALL_DIGITS: u16 = 0x1FF;
+
+fn region_of(i: usize, j: usize) -> usize {
+    i / 3 * 3 + j / 3
+}
+
+struct Search {
+    grid: Grid,
+    rows: [u16; 9],
+    columns: [u16; 9],
+    regions: [u16; 9],
+    solution: Option<Grid>,
+    count: usize,
+    limit: usize,
+}
+
+impl Search {
+    /// None when the givens already break a rule
+    fn new(grid: &Grid, limit: usize) -> Option<Self> {
+        let mut search = Search {
+            grid: *grid,
+            rows: [0; 9],
+            columns: [0; 9],
+            regions: [0; 9],
+            solution: None,
+            count: 0,
+            limit,
+        };
+        for i in 0..9 {
+            for j in 0..9 {
+                let number = grid[i][j];
+                if number == 0 {
+                    continue;
+                }
+                let bit = 1 << (number - 1);
+                if search.used(i, j) & bit != 0 {
+                    return None;
+                }
+                search.toggle(i, j, bit);
+            }
+        }
+        Some(search)
+    }
+
+    fn used(&self, i: usize, j: usize) -> u16 {
+        self.rows[i] | self.columns[j] | self.regions[region_of(i, j)]
+    }
+
+    fn toggle(&mut self, i: usize, j: usize, bit: u16) {
+        self.rows[i] ^= bit;
+        self.columns[j] ^= bit;
+        self.regions[region_of(i, j)] ^= bit;
+    }
+
+    /// Empty cell with the fewest candidates, None when the grid is full
+    fn most_constrained(&self) -> Option<(usize, usize, u16)> {
+        let mut best = None;
+        let mut best_count = 10;
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.grid[i][j] != 0 {
+                    continue;
+                }
+                let candidates = !self.used(i, j) & ALL_DIGITS;
+                if candidates.count_ones() < best_count {
+                    best = Some((i, j, candidates));
+                    best_count = candidates.count_ones();
+                    if best_count <= 1 {
+                        return best;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn run(&mut self) {
+        let (i, j, mut candidates) = if let Some(cell) = self.most_constrained() {
+            cell
+        } else {
+            self.count += 1;
+            if self.solution.is_none() {
+                self.solution = Some(self.grid);
+            }
+            return;
+        };
+
+        while candidates != 0 && self.count < self.limit {
+            let bit = candidates & candidates.wrapping_neg();
+            candidates ^= bit;
+            self.grid[i][j] = bit.trailing_zeros() as u8 + 1;
+            self.toggle(i, j, bit);
+            self.run();
+            self.toggle(i, j, bit);
+            self.grid[i][j] = 0;
+        }
+    }
+}
+
+/// Fills every empty cell, returns false when there is no solution
+pub fn solve_sudoku(grid: &mut Grid) -> bool {
+    let mut search = if let Some(search) = Search::new(grid, 1) {
+        search
+    } else {
+        return false;
+    };
+    search.run();
+    if let Some(solution) = search.solution {
+        *grid = solution;
+        true
+    } else {
+        false
+    }
+}
+
+/// Counts the solutions of the grid, stops searching once `limit` solutions are found
+pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+    if let Some(mut search) = Search::new(grid, limit) {
+        search.run();
+        search.count
+    } else {
+        0
+    }
+}
@@ -0,0 +1,238 @@
+//! Knuth's Algorithm X on dancing links. [`ExactCover`] knows nothing about
+//! sudoku, so other grid sizes or irregular regions only need a different set
+//! of rows; [`solve_sudoku`] and [`count_solutions`] build the classic 9x9 one.
+
+use crate::{validator::is_consistent, Grid};
+
+/// Sparse 0/1 matrix stored as circular doubly linked lists. Node 0 is the
+/// root, nodes `1..=columns` are the column headers, the rest are the 1s.
+pub struct ExactCover {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+    row_start: Vec<usize>,
+    partial: Vec<usize>,
+}
+
+impl ExactCover {
+    pub fn new(columns: usize) -> Self {
+        let nodes = columns + 1;
+        ExactCover {
+            left: (0..nodes).map(|n| (n + nodes - 1) % nodes).collect(),
+            right: (0..nodes).map(|n| (n + 1) % nodes).collect(),
+            up: (0..nodes).collect(),
+            down: (0..nodes).collect(),
+            column: (0..nodes).collect(),
+            row: vec![usize::MAX; nodes],
+            size: vec![0; nodes],
+            row_start: Vec::new(),
+            partial: Vec::new(),
+        }
+    }
+
+    /// Adds a row covering the given 0-based columns and returns its id
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        let id = self.row_start.len();
+        let first = self.left.len();
+        self.row_start.push(first);
+        for (k, &c) in columns.iter().enumerate() {
+            let header = c + 1;
+            let node = first + k;
+            self.left.push(if k == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if k + 1 == columns.len() {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.row.push(id);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+        id
+    }
+
+    /// Puts a row into every solution, returns false when it clashes with an
+    /// already selected row
+    pub fn select(&mut self, row: usize) -> bool {
+        let start = self.row_start[row];
+        let mut node = start;
+        loop {
+            if !self.is_uncovered(self.column[node]) {
+                return false;
+            }
+            node = self.right[node];
+            if node == start {
+                break;
+            }
+        }
+        loop {
+            self.cover(self.column[node]);
+            node = self.right[node];
+            if node == start {
+                break;
+            }
+        }
+        self.partial.push(row);
+        true
+    }
+
+    /// Runs the search, calling `on_solution` with the row ids of every exact
+    /// cover found until `limit` of them were seen. Returns how many were found.
+    pub fn search(&mut self, limit: usize, on_solution: &mut dyn FnMut(&[usize])) -> usize {
+        let mut count = 0;
+        self.search_from(limit, &mut count, on_solution);
+        count
+    }
+
+    fn search_from(
+        &mut self,
+        limit: usize,
+        count: &mut usize,
+        on_solution: &mut dyn FnMut(&[usize]),
+    ) {
+        if self.right[0] == 0 {
+            *count += 1;
+            on_solution(&self.partial);
+            return;
+        }
+
+        let mut c = self.right[0];
+        let mut header = c;
+        while header != 0 {
+            if self.size[header] < self.size[c] {
+                c = header;
+            }
+            header = self.right[header];
+        }
+        if self.size[c] == 0 {
+            return;
+        }
+
+        self.cover(c);
+        let mut r = self.down[c];
+        while r != c && *count < limit {
+            self.partial.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+            self.search_from(limit, count, on_solution);
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            self.partial.pop();
+            r = self.down[r];
+        }
+        self.uncover(c);
+    }
+
+    fn is_uncovered(&self, header: usize) -> bool {
+        self.right[self.left[header]] == header
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+}
+
+/// Exact cover matrix of a 9x9 sudoku with the givens already selected.
+/// Row `(i * 9 + j) * 9 + number - 1` places `number` at row `i`, column `j`.
+fn sudoku_cover(grid: &Grid) -> Option<ExactCover> {
+    if !is_consistent(grid) {
+        return None;
+    }
+    let mut cover = ExactCover::new(324);
+    for i in 0..9 {
+        for j in 0..9 {
+            let region = i / 3 * 3 + j / 3;
+            for n in 0..9 {
+                cover.add_row(&[
+                    i * 9 + j,
+                    81 + i * 9 + n,
+                    162 + j * 9 + n,
+                    243 + region * 9 + n,
+                ]);
+            }
+        }
+    }
+    for i in 0..9 {
+        for j in 0..9 {
+            if grid[i][j] != 0 && !cover.select((i * 9 + j) * 9 + grid[i][j] as usize - 1) {
+                return None;
+            }
+        }
+    }
+    Some(cover)
+}
+
+/// Fills every empty cell, returns false when there is no solution
+pub fn solve_sudoku(grid: &mut Grid) -> bool {
+    let mut cover = if let Some(cover) = sudoku_cover(grid) {
+        cover
+    } else {
+        return false;
+    };
+    let mut solution = None;
+    cover.search(1, &mut |rows| solution = Some(rows.to_vec()));
+    if let Some(rows) = solution {
+        for row in rows {
+            grid[row / 81][row / 9 % 9] = (row % 9) as u8 + 1;
+        }
+        true
+    } else {
+        false
+    }
+}
+
+/// Counts the solutions of the grid, stops searching once `limit` solutions are found
+pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+    if let Some(mut cover) = sudoku_cover(grid) {
+        cover.search(limit, &mut |_| ())
+    } else {
+        0
+    }
+}
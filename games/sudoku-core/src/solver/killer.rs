@@ -0,0 +1,236 @@
+//! The bitmask search with the cage rules of Killer sudoku on top. Every cage keeps
+//! the digits it holds and what is left of its sum, and a cell only gets the digits
+//! that still appear in some set of distinct digits able to finish its cage.
+
+use std::sync::OnceLock;
+
+use crate::{killer::Cage, Grid};
+
+const ALL_DIGITS: u16 = 0x1FF;
+
+fn region_of(i: usize, j: usize) -> usize {
+    i / 3 * 3 + j / 3
+}
+
+/// Sets of distinct digits by how many digits they have and what they add up to,
+/// `COMBINATIONS[size][sum]`
+static COMBINATIONS: OnceLock<Vec<Vec<Vec<u16>>>> = OnceLock::new();
+
+fn combinations(size: u32, sum: u32) -> &'static [u16] {
+    let table = COMBINATIONS.get_or_init(|| {
+        let mut table = vec![vec![Vec::new(); 46]; 10];
+        for set in 0..=ALL_DIGITS {
+            let sum: u32 = (0..9).filter(|d| set & (1 << d) != 0).map(|d| d + 1).sum();
+            table[set.count_ones() as usize][sum as usize].push(set);
+        }
+        table
+    });
+    table
+        .get(size as usize)
+        .and_then(|sums| sums.get(sum as usize))
+        .map_or(&[], |sets| sets.as_slice())
+}
+
+/// Every digit in some set of `size` digits from `available` adding up to `sum`
+fn completions(available: u16, size: u32, sum: u32) -> u16 {
+    combinations(size, sum)
+        .iter()
+        .filter(|&&set| set & !available == 0)
+        .fold(0, |allowed, set| allowed | set)
+}
+
+struct Search {
+    grid: Grid,
+    rows: [u16; 9],
+    columns: [u16; 9],
+    regions: [u16; 9],
+    cage_of: [[Option<usize>; 9]; 9],
+    /// Digits in each cage
+    cage_digits: Vec<u16>,
+    /// Sum each cage still needs
+    cage_left: Vec<u32>,
+    cage_empty: Vec<u32>,
+    solutions: Vec<Grid>,
+    limit: usize,
+    /// Cells tried so far, and how many may be tried before giving up
+    steps: usize,
+    max_steps: usize,
+}
+
+impl Search {
+    /// None when the givens already break a rule
+    fn new(grid: &Grid, cages: &[Cage], limit: usize) -> Option<Self> {
+        let mut search = Search {
+            grid: Grid::new(),
+            rows: [0; 9],
+            columns: [0; 9],
+            regions: [0; 9],
+            cage_of: [[None; 9]; 9],
+            cage_digits: vec![0; cages.len()],
+            cage_left: cages.iter().map(|cage| cage.sum as u32).collect(),
+            cage_empty: cages.iter().map(|cage| cage.cells.len() as u32).collect(),
+            solutions: Vec::new(),
+            limit,
+            steps: 0,
+            max_steps: usize::MAX,
+        };
+        for (index, cage) in cages.iter().enumerate() {
+            for &(i, j) in cage.cells.iter() {
+                if search.cage_of[i][j].replace(index).is_some() {
+                    return None;
+                }
+            }
+        }
+        for i in 0..9 {
+            for j in 0..9 {
+                let number = grid[i][j];
+                if number == 0 {
+                    continue;
+                }
+                let bit = 1 << (number - 1);
+                if search.candidates(i, j) & bit == 0 {
+                    return None;
+                }
+                search.place(i, j, number);
+            }
+        }
+        Some(search)
+    }
+
+    fn candidates(&self, i: usize, j: usize) -> u16 {
+        let free = !(self.rows[i] | self.columns[j] | self.regions[region_of(i, j)]) & ALL_DIGITS;
+        match self.cage_of[i][j] {
+            Some(cage) => free & self.cage_candidates(cage),
+            None => free,
+        }
+    }
+
+    fn cage_candidates(&self, cage: usize) -> u16 {
+        completions(
+            !self.cage_digits[cage] & ALL_DIGITS,
+            self.cage_empty[cage],
+            self.cage_left[cage],
+        )
+    }
+
+    fn place(&mut self, i: usize, j: usize, number: u8) {
+        let bit = 1 << (number - 1);
+        self.grid[i][j] = number;
+        self.rows[i] |= bit;
+        self.columns[j] |= bit;
+        self.regions[region_of(i, j)] |= bit;
+        if let Some(cage) = self.cage_of[i][j] {
+            self.cage_digits[cage] |= bit;
+            self.cage_left[cage] -= number as u32;
+            self.cage_empty[cage] -= 1;
+        }
+    }
+
+    fn remove(&mut self, i: usize, j: usize) {
+        let number = self.grid[i][j];
+        let bit = !(1 << (number - 1));
+        self.grid[i][j] = 0;
+        self.rows[i] &= bit;
+        self.columns[j] &= bit;
+        self.regions[region_of(i, j)] &= bit;
+        if let Some(cage) = self.cage_of[i][j] {
+            self.cage_digits[cage] &= bit;
+            self.cage_left[cage] += number as u32;
+            self.cage_empty[cage] += 1;
+        }
+    }
+
+    /// Empty cell with the fewest candidates, None when the grid is full
+    fn most_constrained(&self) -> Option<(usize, usize, u16)> {
+        // Worked out once per cage rather than once per cell
+        let cage_candidates: Vec<u16> = (0..self.cage_digits.len())
+            .map(|cage| self.cage_candidates(cage))
+            .collect();
+        let mut best = None;
+        let mut best_count = 10;
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.grid[i][j] != 0 {
+                    continue;
+                }
+                let mut candidates =
+                    !(self.rows[i] | self.columns[j] | self.regions[region_of(i, j)]) & ALL_DIGITS;
+                if let Some(cage) = self.cage_of[i][j] {
+                    candidates &= cage_candidates[cage];
+                }
+                if candidates.count_ones() < best_count {
+                    best = Some((i, j, candidates));
+                    best_count = candidates.count_ones();
+                    if best_count <= 1 {
+                        return best;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn run(&mut self) {
+        let (i, j, mut candidates) = if let Some(cell) = self.most_constrained() {
+            cell
+        } else {
+            self.solutions.push(self.grid);
+            return;
+        };
+
+        while candidates != 0 && self.solutions.len() < self.limit && self.steps < self.max_steps {
+            self.steps += 1;
+            let bit = candidates & candidates.wrapping_neg();
+            candidates ^= bit;
+            self.place(i, j, bit.trailing_zeros() as u8 + 1);
+            self.run();
+            self.remove(i, j);
+        }
+    }
+}
+
+/// Up to `limit` solutions of the grid under the cage rules
+pub fn solutions(grid: &Grid, cages: &[Cage], limit: usize) -> Vec<Grid> {
+    if let Some(mut search) = Search::new(grid, cages, limit) {
+        search.run();
+        search.solutions
+    } else {
+        Vec::new()
+    }
+}
+
+/// `solutions`, but gives up with None after trying `max_steps` digits. The generator
+/// uses it to skip layouts that take too long to prove unique.
+pub fn solutions_within(
+    grid: &Grid,
+    cages: &[Cage],
+    limit: usize,
+    max_steps: usize,
+) -> Option<Vec<Grid>> {
+    let Some(mut search) = Search::new(grid, cages, limit) else {
+        return Some(Vec::new());
+    };
+    search.max_steps = max_steps;
+    search.run();
+    if search.steps < max_steps || search.solutions.len() == limit {
+        Some(search.solutions)
+    } else {
+        None
+    }
+}
+
+/// Fills every empty cell, returns false when there is no solution
+pub fn solve_sudoku(grid: &mut Grid, cages: &[Cage]) -> bool {
+    match solutions(grid, cages, 1).first() {
+        Some(solution) => {
+            *grid = *solution;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Counts the solutions of the grid, stops searching once `limit` solutions are found
+pub fn count_solutions(grid: &Grid, cages: &[Cage], limit: usize) -> usize {
+    solutions(grid, cages, limit).len()
+}
@@ -0,0 +1,93 @@
+use crate::{killer::Cage, solver::Solver, Grid};
+
+/// true means fine, false means there is same number(s) horizontally, vertically, or in the same region
+pub fn check_valid(number: u8, i: usize, j: usize, grid: &Grid) -> bool {
+    let start_i = i - i % 3;
+    let start_j = j - j % 3;
+    for k in 0..9 {
+        if grid[start_i + k / 3][start_j + k % 3] == number
+            && !(start_i + k / 3 == i && start_j + k % 3 == j)
+        {
+            return false;
+        }
+
+        if grid[i][k] == number && j != k {
+            return false;
+        }
+
+        if grid[k][j] == number && i != k {
+            return false;
+        }
+    }
+    true
+}
+
+/// `check_valid` plus the cage rules of Killer sudoku: no digit twice in a cage, and the
+/// digits of a cage never go over its sum, meeting it exactly once the cage is full
+pub fn check_valid_in_cages(number: u8, i: usize, j: usize, grid: &Grid, cages: &[Cage]) -> bool {
+    if !check_valid(number, i, j, grid) {
+        return false;
+    }
+    cages
+        .iter()
+        .filter(|cage| cage.cells.contains(&(i, j)))
+        .all(|cage| {
+            let mut total = 0;
+            let mut full = true;
+            for &(ci, cj) in cage.cells.iter() {
+                let other = if (ci, cj) == (i, j) {
+                    number
+                } else {
+                    grid[ci][cj]
+                };
+                if other == 0 {
+                    full = false;
+                } else if other == number && (ci, cj) != (i, j) {
+                    return false;
+                }
+                total += other as u32;
+            }
+            total <= cage.sum as u32 && (!full || total == cage.sum as u32)
+        })
+}
+
+/// true when the grid is completely filled and every cage adds up
+pub fn is_solved_in_cages(grid: &Grid, cages: &[Cage]) -> bool {
+    grid.is_filled()
+        && (0..81)
+            .map(|k| (k / 9, k % 9))
+            .all(|(i, j)| check_valid_in_cages(grid[i][j], i, j, grid, cages))
+}
+
+/// true when every filled cell agrees with the rules, empty cells are ignored
+pub fn is_consistent(grid: &Grid) -> bool {
+    (0..81)
+        .map(|k| (k / 9, k % 9))
+        .all(|(i, j)| grid[i][j] == 0 || check_valid(grid[i][j], i, j, grid))
+}
+
+/// true when the grid is completely filled without breaking any rule
+pub fn is_solved(grid: &Grid) -> bool {
+    grid.is_filled() && is_consistent(grid)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PuzzleStatus {
+    /// Two givens already break a rule
+    Contradiction,
+    NoSolution,
+    Unique,
+    MultipleSolutions,
+}
+
+/// Classifies a puzzle by how many solutions it has, using the given backend
+pub fn check_puzzle(grid: &Grid, solver: &dyn Solver) -> PuzzleStatus {
+    if !is_consistent(grid) {
+        return PuzzleStatus::Contradiction;
+    }
+    match solver.count_solutions(grid, 2) {
+        0 => PuzzleStatus::NoSolution,
+        1 => PuzzleStatus::Unique,
+        _ => PuzzleStatus::MultipleSolutions,
+    }
+}
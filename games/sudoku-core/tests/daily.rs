@@ -0,0 +1,53 @@
+use sudoku_core::{daily, Difficulty};
+
+// 2024-01-01, a Monday
+const NEW_YEAR: u32 = 19_723;
+
+#[test]
+fn formats_dates() {
+    assert_eq!(daily::format_date(0), "1970-01-01");
+    assert_eq!(daily::format_date(NEW_YEAR), "2024-01-01");
+    assert_eq!(daily::format_date(NEW_YEAR + 59), "2024-02-29");
+    assert_eq!(daily::format_date(NEW_YEAR + 366), "2025-01-01");
+}
+
+#[test]
+fn same_day_same_puzzle() {
+    assert_eq!(daily::code(NEW_YEAR), daily::code(NEW_YEAR));
+    assert_ne!(daily::code(NEW_YEAR).seed, daily::code(NEW_YEAR + 1).seed);
+    assert_eq!(
+        daily::code(NEW_YEAR).generate(),
+        daily::code(NEW_YEAR).generate()
+    );
+    assert_eq!(daily::difficulty(NEW_YEAR), Difficulty::Easy);
+    assert_eq!(daily::difficulty(NEW_YEAR + 5), Difficulty::Hard);
+}
+
+#[test]
+fn counts_streaks() {
+    let today = NEW_YEAR + 10;
+    assert_eq!(daily::streak([], today), 0);
+    assert_eq!(daily::streak([today, today - 1, today - 2], today), 3);
+    // Today not played yet keeps yesterday's streak alive
+    assert_eq!(daily::streak([today - 1, today - 2], today), 2);
+    assert_eq!(daily::streak([today, today - 2, today - 3], today), 1);
+    assert_eq!(daily::streak([today - 2], today), 0);
+    assert_eq!(daily::streak([today, today, today - 1], today), 2);
+}
+
+#[test]
+fn finds_longest_streak() {
+    assert_eq!(daily::longest_streak([]), 0);
+    assert_eq!(daily::longest_streak([NEW_YEAR]), 1);
+    assert_eq!(
+        daily::longest_streak([
+            NEW_YEAR + 9,
+            NEW_YEAR,
+            NEW_YEAR + 1,
+            NEW_YEAR + 2,
+            NEW_YEAR + 8
+        ]),
+        3
+    );
+    assert_eq!(daily::longest_streak([NEW_YEAR, NEW_YEAR, NEW_YEAR + 1]), 2);
+}
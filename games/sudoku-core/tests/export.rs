@@ -0,0 +1,54 @@
+use sudoku_core::{
+    export::{to_line, to_svg, to_svg_with_cages},
+    import::parse_line,
+    killer::Cage,
+    solver::{Bitmask, Solver},
+    Difficulty, Grid, PuzzleCode,
+};
+
+const LINE: &str =
+    "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+#[test]
+fn line_round_trips_through_import() {
+    let grid = parse_line(LINE).unwrap();
+    assert_eq!(to_line(&grid), LINE);
+}
+
+#[test]
+fn svg_draws_givens_and_entries() {
+    let givens = parse_line(LINE).unwrap();
+    let svg = to_svg(&givens, None);
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<line").count(), 20);
+    assert_eq!(svg.matches("stroke-width=\"3\"").count(), 8);
+    assert_eq!(svg.matches("<text").count(), givens.clue_count());
+
+    let mut solution = givens;
+    assert!(Bitmask.solve(&mut solution));
+    let svg = to_svg(&givens, Some(&solution));
+    assert_eq!(svg.matches("<text").count(), 81);
+    assert_eq!(svg.matches("font-weight").count(), givens.clue_count());
+}
+
+#[test]
+fn svg_outlines_cages_with_their_sums() {
+    // An L of three cells, the outline runs along eight cell sides
+    let cage = Cage {
+        cells: vec![(0, 0), (1, 0), (1, 1)],
+        sum: 12,
+    };
+    let svg = to_svg_with_cages(&Grid::new(), None, &[cage]);
+    assert_eq!(svg.matches("stroke-dasharray").count(), 8);
+    assert_eq!(svg.matches("<text").count(), 1);
+    assert!(svg.contains(">12</text>"));
+
+    let killer = PuzzleCode::new_killer(Difficulty::Easy, 1).generate_killer();
+    let svg = to_svg_with_cages(&killer.puzzle.givens, None, &killer.cages);
+    assert_eq!(svg.matches("<text").count(), killer.cages.len());
+    assert_eq!(
+        svg.matches("<line").count() - 20,
+        svg.matches("stroke-dasharray").count()
+    );
+}
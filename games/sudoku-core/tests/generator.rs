@@ -0,0 +1,68 @@
+use sudoku_core::{generate_sudoku, generator, solver, validator, Difficulty, PuzzleCode};
+
+#[test]
+fn givens_come_from_solution() {
+    for difficulty in [Difficulty::Easy, Difficulty::Intermediate, Difficulty::Hard] {
+        let puzzle = generate_sudoku(difficulty, &mut rand::thread_rng());
+        assert!(validator::is_solved(&puzzle.solution));
+        for i in 0..9 {
+            for j in 0..9 {
+                let given = puzzle.givens[i][j];
+                assert!(given == 0 || given == puzzle.solution[i][j]);
+            }
+        }
+    }
+}
+
+#[test]
+fn none_difficulty_is_empty() {
+    let puzzle = generate_sudoku(Difficulty::None, &mut rand::thread_rng());
+    assert_eq!(puzzle.givens.clue_count(), 0);
+}
+
+#[test]
+fn puzzles_have_a_single_solution() {
+    for difficulty in [Difficulty::Easy, Difficulty::Intermediate, Difficulty::Hard] {
+        let puzzle = generate_sudoku(difficulty, &mut rand::thread_rng());
+        assert_eq!(solver::count_solutions(&puzzle.givens, 2), 1);
+        assert!(puzzle.givens.clue_count() >= 81 - difficulty.cells_to_remove());
+    }
+}
+
+#[test]
+fn solutions_are_random() {
+    let mut rng = rand::thread_rng();
+    let first = generator::random_solution(&mut rng);
+    assert!(validator::is_solved(&first));
+    let repeated = (0..5)
+        .map(|_| generator::random_solution(&mut rng))
+        .filter(|grid| {
+            assert!(validator::is_solved(grid));
+            *grid == first
+        })
+        .count();
+    assert_eq!(repeated, 0);
+}
+
+#[test]
+fn same_code_gives_same_puzzle() {
+    let code = PuzzleCode::new(Difficulty::Intermediate, 0x0F3A9C21);
+    assert_eq!(code.generate(), code.generate());
+    assert_ne!(
+        code.generate(),
+        PuzzleCode::new(Difficulty::Intermediate, 0x0F3A9C22).generate()
+    );
+}
+
+#[test]
+fn code_round_trips_through_text() {
+    let code = PuzzleCode::new(Difficulty::Hard, 0x0F3A9C21);
+    assert_eq!(code.to_string(), "H-0F3A9C21");
+    assert_eq!("H-0F3A9C21".parse(), Ok(code));
+    assert_eq!(" h0f3a9c21 ".parse(), Ok(code));
+    assert_eq!("e-1".parse(), Ok(PuzzleCode::new(Difficulty::Easy, 1)));
+    assert!("X-0F3A9C21".parse::<PuzzleCode>().is_err());
+    assert!("H-".parse::<PuzzleCode>().is_err());
+    assert!("H-0F3A9C21F".parse::<PuzzleCode>().is_err());
+    assert!("H-+F3A".parse::<PuzzleCode>().is_err());
+}
@@ -0,0 +1,86 @@
+use sudoku_core::{
+    import::{import_puzzles, parse_line, ImportError},
+    solver::Bitmask,
+    validator::{self, PuzzleStatus},
+};
+
+const LINE: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+#[test]
+fn parses_dots_and_zeros_alike() {
+    let dotted = LINE.replace('0', ".");
+    assert_eq!(parse_line(LINE).unwrap(), parse_line(&dotted).unwrap());
+    assert_eq!(parse_line(LINE).unwrap()[0][0], 5);
+}
+
+#[test]
+fn reads_sdk_layout() {
+    let sdk = "[Puzzle]\n#A someone\n#D a classic\n53..7....\n6..195...\n.98....6.\n8...6...3\n\
+               4..8.3..1\n7...2...6\n.6....28.\n...419..5\n....8..79\n";
+    let puzzles = import_puzzles(sdk, &Bitmask).unwrap();
+    assert_eq!(puzzles.len(), 1);
+    assert_eq!(puzzles[0].line, 4);
+    assert_eq!(puzzles[0].givens, parse_line(LINE).unwrap());
+    assert_eq!(puzzles[0].status, PuzzleStatus::Unique);
+    assert!(validator::is_solved(&puzzles[0].solution.unwrap()));
+}
+
+#[test]
+fn reads_multi_puzzle_files() {
+    let text = format!("# collection\n{}\n\n{}\n", LINE, LINE.replace('0', "."));
+    let puzzles = import_puzzles(&text, &Bitmask).unwrap();
+    assert_eq!(puzzles.len(), 2);
+    assert_eq!(puzzles[1].line, 4);
+    assert!(puzzles.iter().all(|puzzle| puzzle.warning().is_none()));
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    let text = format!("{}\n{}\n", LINE, &LINE[1..]);
+    assert_eq!(
+        import_puzzles(&text, &Bitmask),
+        Err(ImportError::WrongLength { line: 2, len: 80 })
+    );
+    let text = LINE.replacen('0', "x", 1);
+    assert_eq!(
+        import_puzzles(&text, &Bitmask),
+        Err(ImportError::InvalidCharacter {
+            line: 1,
+            character: 'x'
+        })
+    );
+    assert_eq!(
+        import_puzzles("53..7....\n6..195...\n", &Bitmask),
+        Err(ImportError::IncompleteGrid { line: 1, rows: 2 })
+    );
+    assert_eq!(
+        import_puzzles("# nothing\n", &Bitmask),
+        Err(ImportError::NoPuzzles)
+    );
+}
+
+#[test]
+fn rejects_contradictory_givens() {
+    let text = LINE.replacen('0', "5", 1);
+    assert_eq!(
+        import_puzzles(&text, &Bitmask),
+        Err(ImportError::Contradiction { line: 1 })
+    );
+}
+
+#[test]
+fn warns_about_unsolvable_and_ambiguous_puzzles() {
+    // A 1 next to the 5 and 3 leaves r1c3 with no candidates
+    let unsolvable = format!("531{}", &LINE[3..]);
+    let puzzle = import_puzzles(&unsolvable, &Bitmask).unwrap()[0];
+    assert_eq!(puzzle.status, PuzzleStatus::NoSolution);
+    assert!(puzzle.warning().is_some());
+    assert!(puzzle.puzzle().is_none());
+
+    let empty = ".".repeat(81);
+    let puzzle = import_puzzles(&empty, &Bitmask).unwrap()[0];
+    assert_eq!(puzzle.status, PuzzleStatus::MultipleSolutions);
+    assert!(puzzle.warning().is_some());
+    assert!(puzzle.puzzle().is_some());
+}
@@ -0,0 +1,60 @@
+use sudoku_core::{
+    killer::{self, Cage},
+    solver, validator, Difficulty, Grid, PuzzleCode,
+};
+
+#[test]
+fn generated_cages_have_one_solution() {
+    for (difficulty, seed) in [(Difficulty::Easy, 1), (Difficulty::Hard, 2)] {
+        let code = PuzzleCode::new_killer(difficulty, seed);
+        let killer = code.generate_killer();
+        let solution = killer.puzzle.solution;
+        assert!(validator::is_solved_in_cages(&solution, &killer.cages));
+        assert_eq!(killer.puzzle.givens, Grid::new());
+
+        let mut covered = [[0; 9]; 9];
+        for cage in killer.cages.iter() {
+            assert!(cage.cells.len() <= killer::max_cage_size(difficulty));
+            for &(i, j) in cage.cells.iter() {
+                covered[i][j] += 1;
+            }
+        }
+        assert_eq!(covered, [[1; 9]; 9]);
+
+        let mut grid = killer.puzzle.givens;
+        assert_eq!(solver::killer::count_solutions(&grid, &killer.cages, 2), 1);
+        assert!(solver::killer::solve_sudoku(&mut grid, &killer.cages));
+        assert_eq!(grid, solution);
+        assert_eq!(code.generate_killer(), killer);
+    }
+}
+
+#[test]
+fn cages_limit_entries() {
+    let cages = [Cage {
+        cells: vec![(0, 0), (0, 1)],
+        sum: 10,
+    }];
+    let mut grid = Grid::new();
+    grid[0][0] = 3;
+    assert!(validator::check_valid_in_cages(7, 0, 1, &grid, &cages));
+    // Full but off the sum
+    assert!(!validator::check_valid_in_cages(6, 0, 1, &grid, &cages));
+    // Over the sum before the cage is full
+    let cages = [Cage {
+        cells: vec![(0, 0), (1, 0), (2, 0)],
+        sum: 8,
+    }];
+    assert!(!validator::check_valid_in_cages(6, 1, 0, &grid, &cages));
+    assert!(validator::check_valid_in_cages(4, 1, 0, &grid, &cages));
+    // Outside every cage only the usual rules count
+    assert!(validator::check_valid_in_cages(9, 5, 5, &grid, &cages));
+}
+
+#[test]
+fn killer_codes_round_trip() {
+    let code = PuzzleCode::new_killer(Difficulty::Hard, 0x0F3A9C21);
+    assert_eq!(code.to_string(), "KH-0F3A9C21");
+    assert_eq!("kh0f3a9c21".parse::<PuzzleCode>(), Ok(code));
+    assert_ne!(code, PuzzleCode::new(Difficulty::Hard, 0x0F3A9C21));
+}
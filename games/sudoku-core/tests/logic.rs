@@ -0,0 +1,86 @@
+use sudoku_core::{
+    generate_sudoku,
+    logic::{self, LogicSolver, Technique},
+    Difficulty, Grid, PuzzleCode,
+};
+
+fn grid_from(line: &str) -> Grid {
+    let mut grid = Grid::new();
+    for (k, c) in line.chars().enumerate() {
+        grid[k / 9][k % 9] = c.to_digit(10).unwrap() as u8;
+    }
+    grid
+}
+
+const SINGLES: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+const X_WING: &str =
+    "000000207000000400070406010050708004000009600098010000203090001010000900540001008";
+
+#[test]
+fn rates_by_hardest_technique() {
+    let easy = logic::rate(&grid_from(SINGLES));
+    assert!(easy.solved);
+    assert_eq!(easy.difficulty(), Difficulty::Easy);
+
+    let hard = logic::rate(&grid_from(X_WING));
+    assert!(hard.solved);
+    assert_eq!(hard.hardest, Some(Technique::XWing));
+    assert_eq!(hard.difficulty(), Difficulty::Hard);
+
+    let stuck = logic::rate(&Grid::new());
+    assert!(!stuck.solved);
+    assert_eq!(stuck.difficulty(), Difficulty::Hard);
+}
+
+#[test]
+fn explains_steps() {
+    let mut solver = LogicSolver::new(&grid_from(X_WING));
+    let mut techniques = Vec::new();
+    while let Some(step) = solver.next_step() {
+        assert!(step
+            .explanation
+            .starts_with(&format!("{}: ", step.technique)));
+        if step.technique == Technique::HiddenSingle {
+            let (i, j, number) = step.placement.unwrap();
+            assert!(step.explanation.contains(&format!(
+                "{} can only go in r{}c{} within ",
+                number,
+                i + 1,
+                j + 1
+            )));
+        }
+        techniques.push(step.technique);
+        solver.apply(&step);
+    }
+    assert!(techniques.contains(&Technique::XWing));
+}
+
+#[test]
+fn steps_agree_with_solution() {
+    for difficulty in [Difficulty::Easy, Difficulty::Intermediate, Difficulty::Hard] {
+        for _ in 0..5 {
+            let puzzle = generate_sudoku(difficulty, &mut rand::thread_rng());
+            let mut solver = LogicSolver::new(&puzzle.givens);
+            while let Some(step) = solver.next_step() {
+                if let Some((i, j, number)) = step.placement {
+                    assert_eq!(puzzle.solution[i][j], number, "{}", step.explanation);
+                }
+                for &(i, j, number) in step.eliminations.iter() {
+                    assert_ne!(puzzle.solution[i][j], number, "{}", step.explanation);
+                }
+                solver.apply(&step);
+            }
+        }
+    }
+}
+
+#[test]
+fn generated_rating_matches_difficulty() {
+    for difficulty in [Difficulty::Easy, Difficulty::Intermediate, Difficulty::Hard] {
+        for seed in 0..3 {
+            let puzzle = PuzzleCode::new(difficulty, seed).generate();
+            assert_eq!(logic::rate(&puzzle.givens).difficulty(), difficulty);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use sudoku_core::{
+    generate_sudoku,
+    solver::{self, dlx::ExactCover, Backtrack, Bitmask, DancingLinks, Solver},
+    validator::{self, PuzzleStatus},
+    Difficulty, Grid,
+};
+
+fn grid_from(rows: [&str; 9]) -> Grid {
+    let mut grid = Grid::new();
+    for (i, row) in rows.iter().enumerate() {
+        for (j, c) in row.chars().enumerate() {
+            grid[i][j] = c.to_digit(10).unwrap() as u8;
+        }
+    }
+    grid
+}
+
+fn puzzle() -> Grid {
+    grid_from([
+        "530070000",
+        "600195000",
+        "098000060",
+        "800060003",
+        "400803001",
+        "700020006",
+        "060000280",
+        "000419005",
+        "000080079",
+    ])
+}
+
+#[test]
+fn solves_known_puzzle() {
+    let mut grid = puzzle();
+    assert!(solver::solve_sudoku(&mut grid));
+    assert!(validator::is_solved(&grid));
+    assert_eq!(
+        grid,
+        grid_from([
+            "534678912",
+            "672195348",
+            "198342567",
+            "859761423",
+            "426853791",
+            "713924856",
+            "961537284",
+            "287419635",
+            "345286179",
+        ])
+    );
+}
+
+#[test]
+fn keeps_givens_when_solving() {
+    let givens = puzzle();
+    let mut grid = givens;
+    solver::solve_sudoku(&mut grid);
+    for i in 0..9 {
+        for j in 0..9 {
+            if givens[i][j] != 0 {
+                assert_eq!(grid[i][j], givens[i][j]);
+            }
+        }
+    }
+}
+
+#[test]
+fn rejects_contradictory_grid() {
+    let mut grid = puzzle();
+    grid[0][2] = 5;
+    assert!(!validator::is_consistent(&grid));
+    assert!(!solver::solve_sudoku(&mut grid));
+}
+
+#[test]
+fn check_valid_ignores_own_cell() {
+    let grid = puzzle();
+    assert!(validator::check_valid(5, 0, 0, &grid));
+    assert!(!validator::check_valid(3, 0, 2, &grid));
+    assert!(!validator::check_valid(6, 0, 2, &grid));
+    assert!(!validator::check_valid(9, 0, 2, &grid));
+    assert!(validator::check_valid(4, 0, 2, &grid));
+}
+
+#[test]
+fn counts_solutions_up_to_limit() {
+    assert_eq!(solver::count_solutions(&puzzle(), 2), 1);
+    assert!(solver::has_unique_solution(&puzzle()));
+    assert_eq!(solver::count_solutions(&Grid::new(), 2), 2);
+    assert_eq!(solver::count_solutions(&Grid::new(), 5), 5);
+
+    // 6 7 / 7 6 swaps freely between rows 0 and 3
+    let mut ambiguous = puzzle();
+    solver::solve_sudoku(&mut ambiguous);
+    for (i, j) in [(0, 3), (0, 4), (3, 3), (3, 4)] {
+        ambiguous[i][j] = 0;
+    }
+    assert_eq!(solver::count_solutions(&ambiguous, 10), 2);
+    assert!(!solver::has_unique_solution(&ambiguous));
+}
+
+#[test]
+fn contradictory_grid_has_no_solution() {
+    let mut grid = puzzle();
+    grid[0][2] = 5;
+    assert_eq!(solver::count_solutions(&grid, 2), 0);
+    assert!(!solver::has_unique_solution(&grid));
+}
+
+#[test]
+fn backends_agree() {
+    let backends: [&dyn Solver; 3] = [&Backtrack, &Bitmask, &DancingLinks];
+    let mut rng = rand::thread_rng();
+    for _ in 0..3 {
+        let puzzle = generate_sudoku(Difficulty::Hard, &mut rng);
+        let mut loose = puzzle.givens;
+        for (i, j) in [(0, 0), (4, 4), (8, 8)] {
+            loose[i][j] = 0;
+        }
+        let mut broken = puzzle.givens;
+        broken[0][0] = 0;
+        broken[0][1] = puzzle.solution[0][0];
+        broken[1][0] = 0;
+        for grid in [puzzle.givens, loose, broken] {
+            let counts: Vec<usize> = backends
+                .iter()
+                .map(|solver| solver.count_solutions(&grid, 3))
+                .collect();
+            assert!(
+                counts.iter().all(|&count| count == counts[0]),
+                "{:?}",
+                counts
+            );
+        }
+
+        for solver in backends {
+            let mut solved = puzzle.givens;
+            assert!(solver.solve(&mut solved));
+            assert_eq!(solved, puzzle.solution);
+            assert_eq!(
+                validator::check_puzzle(&puzzle.givens, solver),
+                PuzzleStatus::Unique
+            );
+        }
+    }
+
+    // Two 5s in the first row, every backend has to give up straight away
+    let mut contradiction = Grid::new();
+    contradiction[0][0] = 5;
+    contradiction[0][8] = 5;
+    for solver in backends {
+        let mut grid = contradiction;
+        assert!(!solver.solve(&mut grid));
+        assert_eq!(solver.count_solutions(&contradiction, 2), 0);
+    }
+}
+
+#[test]
+fn dlx_and_bitmask_agree_on_many_puzzles() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        let puzzle = generate_sudoku(Difficulty::Intermediate, &mut rng);
+        let mut grid = puzzle.givens;
+        let mut removed = 0;
+        for i in 0..9 {
+            for j in 0..9 {
+                if grid[i][j] != 0 && removed < 4 {
+                    grid[i][j] = 0;
+                    removed += 1;
+                }
+            }
+        }
+        assert_eq!(
+            DancingLinks.count_solutions(&grid, 50),
+            Bitmask.count_solutions(&grid, 50)
+        );
+    }
+}
+
+#[test]
+fn check_puzzle_classifies() {
+    let mut contradiction = puzzle();
+    contradiction[0][2] = 5;
+    assert_eq!(
+        validator::check_puzzle(&contradiction, &DancingLinks),
+        PuzzleStatus::Contradiction
+    );
+    // 1 does not clash with anything at r1c3, but the only solution has 4 there
+    let mut no_solution = puzzle();
+    no_solution[0][2] = 1;
+    for solver in [&Bitmask as &dyn Solver, &DancingLinks] {
+        assert_eq!(
+            validator::check_puzzle(&no_solution, solver),
+            PuzzleStatus::NoSolution
+        );
+    }
+    assert_eq!(
+        validator::check_puzzle(&Grid::new(), &DancingLinks),
+        PuzzleStatus::MultipleSolutions
+    );
+}
+
+#[test]
+fn exact_cover_finds_knuth_example() {
+    // Knuth's example from the dancing links paper, the only cover is rows 0, 3 and 4
+    let mut cover = ExactCover::new(7);
+    cover.add_row(&[2, 4, 5]);
+    cover.add_row(&[0, 3, 6]);
+    cover.add_row(&[1, 2, 5]);
+    cover.add_row(&[0, 3]);
+    cover.add_row(&[1, 6]);
+    cover.add_row(&[3, 4, 6]);
+    let mut found = Vec::new();
+    assert_eq!(cover.search(10, &mut |rows| found.push(rows.to_vec())), 1);
+    found[0].sort();
+    assert_eq!(found, vec![vec![0, 3, 4]]);
+}
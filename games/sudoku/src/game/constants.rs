@@ -1 +1,5 @@
 pub const SCREEN_SIZE: (f32, f32) = (720., 480.);
+/// Name scores are saved under when the player skips typing one
+pub const DEFAULT_PLAYER_NAME: &str = "Player";
+/// Strikes a challenge can be played with, in the order the setting cycles through
+pub const MISTAKE_LIMITS: [Option<u32>; 4] = [None, Some(1), Some(3), Some(5)];
@@ -1,16 +1,40 @@
-use super::entity::Difficulty;
+use super::entity::{Difficulty, SavedGame, Score};
+use sudoku_core::{import::ImportedPuzzle, PuzzleCode};
+
 pub struct AddOnContext {
     pub difficulty: Option<Difficulty>,
+    /// Set when the player typed a code, otherwise a random one is made for `difficulty`
+    pub puzzle_code: Option<PuzzleCode>,
+    /// Day number when `puzzle_code` is the daily puzzle
+    pub daily: Option<u32>,
+    /// Game picked with Continue, taken by the next `Playing`
+    pub saved_game: Option<SavedGame>,
+    /// Score `Playing` just saved, highlighted by the next `LeaderBoard`
+    pub new_score: Option<Score>,
+    /// Puzzle picked in the library, taken by the next `Playing`
+    pub imported: Option<ImportedPuzzle>,
 }
 
 impl AddOnContext {
     pub fn new() -> Self {
-        AddOnContext { difficulty: None }
+        AddOnContext {
+            difficulty: None,
+            puzzle_code: None,
+            daily: None,
+            saved_game: None,
+            new_score: None,
+            imported: None,
+        }
     }
 
     pub fn new_forced() -> Self {
         AddOnContext {
             difficulty: Some(Difficulty::None),
+            puzzle_code: None,
+            daily: None,
+            saved_game: None,
+            new_score: None,
+            imported: None,
         }
     }
 }
@@ -1,4 +1,4 @@
-use ggez::{event, graphics, mint::Point2, Context, GameResult};
+use ggez::{event, graphics, input::keyboard::KeyInput, mint::Point2, Context, GameResult};
 
 // use crate::game::entity::*;
 use crate::game::{
@@ -6,8 +6,10 @@ use crate::game::{
     context,
     game_states::{
         leader_board::LeaderBoard, main_menu::MainMenu, playing::Playing,
-        select_difficulty::SelectDifficulty, *,
+        puzzle_library::PuzzleLibrary, select_difficulty::SelectDifficulty, statistics::Statistics,
+        *,
     },
+    saves,
 };
 
 pub fn run() {
@@ -16,6 +18,10 @@ pub fn run() {
         .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_SIZE.0, SCREEN_SIZE.1))
         .build()
         .unwrap();
+    // A failure here shows up again, on screen, the first time something is saved
+    if let Err(e) = saves::init(ctx.fs.user_data_dir()) {
+        eprintln!("Couldn't prepare the save directory: {}", e);
+    }
 
     let state = App::new(&ctx, GameState::MainMenu);
     event::run(ctx, events_loop, state);
@@ -31,8 +37,14 @@ impl App {
         let current_state: Box<dyn StateTrait> = match initial_state {
             GameState::MainMenu => Box::new(MainMenu::new(ctx)),
             GameState::SelectDifficulty => Box::new(SelectDifficulty::new(ctx)),
-            GameState::Playing => Box::new(Playing::new(ctx, &context::AddOnContext::new_forced())),
-            GameState::LeaderBoard => Box::new(LeaderBoard::new(ctx)),
+            GameState::Playing => {
+                Box::new(Playing::new(ctx, &mut context::AddOnContext::new_forced()))
+            }
+            GameState::LeaderBoard => {
+                Box::new(LeaderBoard::new(ctx, &mut context::AddOnContext::new()))
+            }
+            GameState::PuzzleLibrary => Box::new(PuzzleLibrary::new(ctx)),
+            GameState::Statistics => Box::new(Statistics::new(ctx)),
         };
         App {
             current_state,
@@ -44,8 +56,10 @@ impl App {
         let new_state: Box<dyn StateTrait> = match new_state {
             GameState::MainMenu => Box::new(MainMenu::new(ctx)),
             GameState::SelectDifficulty => Box::new(SelectDifficulty::new(ctx)),
-            GameState::Playing => Box::new(Playing::new(ctx, &self.addon_ctx)),
-            GameState::LeaderBoard => Box::new(LeaderBoard::new(ctx)),
+            GameState::Playing => Box::new(Playing::new(ctx, &mut self.addon_ctx)),
+            GameState::LeaderBoard => Box::new(LeaderBoard::new(ctx, &mut self.addon_ctx)),
+            GameState::PuzzleLibrary => Box::new(PuzzleLibrary::new(ctx)),
+            GameState::Statistics => Box::new(Statistics::new(ctx)),
         };
         let old_state = std::mem::replace(&mut self.current_state, new_state);
         std::mem::drop(old_state);
@@ -79,4 +93,34 @@ impl event::EventHandler for App {
             .mouse_button_down_event(ctx, &button, &Point2 { x, y })?;
         Ok(())
     }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, x: f32, y: f32) -> GameResult {
+        self.current_state.mouse_wheel_event(ctx, x, y)?;
+        Ok(())
+    }
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        input: KeyInput,
+        _repeated: bool,
+    ) -> GameResult {
+        self.current_state.key_down_event(ctx, &input)?;
+        Ok(())
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, character: char) -> GameResult {
+        self.current_state.text_input_event(ctx, character)?;
+        Ok(())
+    }
+
+    fn focus_event(&mut self, ctx: &mut Context, gained: bool) -> GameResult {
+        self.current_state.focus_event(ctx, gained)?;
+        Ok(())
+    }
+
+    fn quit_event(&mut self, ctx: &mut Context) -> GameResult<bool> {
+        self.current_state.quit_event(ctx)?;
+        Ok(false)
+    }
 }
@@ -1,12 +1,21 @@
 use ggez::{
     glam::Vec2,
-    graphics::{self, Mesh, Rect, Text, TextFragment},
+    graphics::{self, Mesh, MeshBuilder, Rect, Text},
     Context, GameResult,
 };
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{collections::BTreeMap, fmt, time::Duration};
+use sudoku_core::{
+    import::ImportedPuzzle, killer::Cage, solver::SolverKind, validator, Grid, Puzzle, PuzzleCode,
+};
+
+pub use sudoku_core::Difficulty;
 
 const GRID_DIMENSION: (f32, f32) = (40., 40.);
+/// How far cage outlines sit inside the cell borders, in cells
+const CAGE_INSET: f32 = 0.1;
+/// Length of each dash of a cage outline, and of the gaps between them
+const CAGE_DASH: f32 = 3.;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Condition {
@@ -15,55 +24,259 @@ pub enum Condition {
     Wrong,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
-pub enum Difficulty {
-    None,
-    Easy,
-    Intermediate,
-    Hard,
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Score {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub time: Duration,
+    #[serde(default)]
+    pub hints: u32,
+    /// Day number when this was the daily puzzle
+    #[serde(default)]
+    pub daily: Option<u32>,
+    /// Digits placed that didn't match the solution. Zero on scores from before it was counted
+    #[serde(default)]
+    pub mistakes: u32,
+    /// Strikes allowed when this was played as a challenge
+    #[serde(default)]
+    pub mistake_limit: Option<u32>,
+    /// The most telling check used during the game
+    #[serde(default)]
+    pub check_mode: CheckMode,
+    /// Played as Killer sudoku
+    #[serde(default)]
+    pub killer: bool,
 }
 
-impl From<Difficulty> for TextFragment {
-    fn from(value: Difficulty) -> Self {
-        match value {
-            Difficulty::None => TextFragment::new("None"),
-            Difficulty::Easy => TextFragment::new("Easy"),
-            Difficulty::Intermediate => TextFragment::new("Intermediate"),
-            Difficulty::Hard => TextFragment::new("Hard"),
+impl Score {
+    pub fn new(name: &str, difficulty: Difficulty, time: Duration, hints: u32) -> Self {
+        Score {
+            name: String::from(name),
+            difficulty,
+            time,
+            hints,
+            daily: None,
+            mistakes: 0,
+            mistake_limit: None,
+            check_mode: CheckMode::default(),
+            killer: false,
         }
     }
 }
 
+/// Layout of `scores.ron` written by this version
+pub const RECORDS_VERSION: u32 = 2;
+
+/// What the leaderboard and statistics are built from.
+/// Version 1 files were only the list of scores.
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct Score {
+pub struct Records {
+    pub version: u32,
+    /// Games begun per difficulty, a resumed game isn't counted again
+    pub started: BTreeMap<Difficulty, u32>,
+    pub scores: Vec<Score>,
+}
+
+impl Default for Records {
+    fn default() -> Self {
+        Records {
+            version: RECORDS_VERSION,
+            started: BTreeMap::new(),
+            scores: Vec::new(),
+        }
+    }
+}
+
+impl Records {
+    /// Upgrades a version 1 list. Unfinished games weren't kept track of then,
+    /// so every finished one counts as started.
+    pub fn from_scores(scores: Vec<Score>) -> Self {
+        let mut started = BTreeMap::new();
+        for score in scores.iter() {
+            *started.entry(score.difficulty).or_insert(0) += 1;
+        }
+        Records {
+            version: RECORDS_VERSION,
+            started,
+            scores,
+        }
+    }
+}
+
+/// How a digit gets into a cell
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+pub enum InputMode {
+    /// Pick a digit on the `NumberBoard`, then click the cells to fill
+    #[default]
+    DigitFirst,
+    /// Click a cell to select it, then pick the digit for it
+    CellFirst,
+}
+
+impl fmt::Display for InputMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputMode::DigitFirst => write!(f, "Digit first"),
+            InputMode::CellFirst => write!(f, "Cell first"),
+        }
+    }
+}
+
+/// Which entries are shown in red, ordered from least to most telling
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize, Debug)]
+pub enum CheckMode {
+    Off,
+    /// Digits repeated in a row, column or region
+    #[default]
+    Conflicts,
+    /// Digits that differ from the solution
+    Solution,
+}
+
+impl fmt::Display for CheckMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckMode::Off => write!(f, "Off"),
+            CheckMode::Conflicts => write!(f, "Conflicts"),
+            CheckMode::Solution => write!(f, "Solution"),
+        }
+    }
+}
+
+/// Player preferences kept between runs
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct Settings {
+    #[serde(default)]
+    pub input_mode: InputMode,
+    /// Last name typed after a win, offered again next time
+    #[serde(default)]
+    pub player_name: Option<String>,
+    /// Strikes allowed in new games picked from the difficulty screen, None for no limit
+    #[serde(default)]
+    pub mistake_limit: Option<u32>,
+    /// Ignored in challenges, which always check against the solution
+    #[serde(default)]
+    pub check_mode: CheckMode,
+    /// New games picked from the difficulty screen are Killer sudoku
+    #[serde(default)]
+    pub killer: bool,
+    /// Backend used to generate classic puzzles and check imported ones
+    #[serde(default)]
+    pub solver: SolverKind,
+}
+
+/// Puzzles read from one file of the puzzles folder
+#[derive(Clone, Debug)]
+pub struct Pack {
+    /// File name without the extension
     pub name: String,
+    pub puzzles: Vec<ImportedPuzzle>,
+}
+
+/// Everything needed to pick an unfinished game back up
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SavedGame {
+    /// None for imported puzzles
+    pub puzzle_code: Option<PuzzleCode>,
     pub difficulty: Difficulty,
+    pub givens: Grid,
+    pub solution: Grid,
+    pub numbers: Grid,
+    pub notes: [[u16; 9]; 9],
+    pub history: History,
     pub time: Duration,
+    pub hints: u32,
+    #[serde(default)]
+    pub daily: Option<u32>,
+    #[serde(default)]
+    pub mistakes: u32,
+    #[serde(default)]
+    pub mistake_limit: Option<u32>,
+    #[serde(default)]
+    pub check_mode: CheckMode,
+    /// Empty unless it is a Killer game
+    #[serde(default)]
+    pub cages: Vec<Cage>,
 }
 
-impl Score {
-    pub fn new(name: &str, difficulty: Difficulty, time: Duration) -> Self {
-        Score {
-            name: String::from(name),
-            difficulty,
-            time,
+impl SavedGame {
+    pub fn puzzle(&self) -> Puzzle {
+        Puzzle {
+            givens: self.givens,
+            solution: self.solution,
+        }
+    }
+}
+
+/// One cell before and after an edit
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct CellChange {
+    pub i: usize,
+    pub j: usize,
+    pub number: (u8, u8),
+    pub notes: (u16, u16),
+}
+
+/// Undo and redo stacks, every entry is the set of cells one move changed
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct History {
+    undo: Vec<Vec<CellChange>>,
+    redo: Vec<Vec<CellChange>>,
+}
+
+impl History {
+    pub fn record(&mut self, changes: Vec<CellChange>) {
+        if !changes.is_empty() {
+            self.undo.push(changes);
+            self.redo.clear();
         }
     }
+
+    pub fn undo(&mut self) -> Option<&Vec<CellChange>> {
+        let changes = self.undo.pop()?;
+        self.redo.push(changes);
+        self.redo.last()
+    }
+
+    pub fn redo(&mut self) -> Option<&Vec<CellChange>> {
+        let changes = self.redo.pop()?;
+        self.undo.push(changes);
+        self.undo.last()
+    }
 }
 
 pub struct GameBoard {
     pub grid_rect: [[Rect; 9]; 9],
-    pub numbers: [[u8; 9]; 9],
+    pub numbers: Grid,
+    /// The generated solution, entries are checked against it in `CheckMode::Solution`
+    pub solution: Grid,
     pub number_state: [[Condition; 9]; 9],
+    /// Pencil marks, bit `n - 1` set means `n` is noted in the cell
+    pub notes: [[u16; 9]; 9],
     pub number_selected: u8,
+    /// Cells a hint is based on
+    pub hint_cells: Vec<(usize, usize)>,
+    /// Cells a hint places a digit in or removes candidates from
+    pub hint_targets: Vec<(usize, usize)>,
+    /// Cell the keyboard acts on
+    pub cursor: (usize, usize),
+    /// Shades the row, column and region of the cursor
+    pub highlight_cursor_peers: bool,
+    /// Killer cages, empty in a classic game
+    pub cages: Vec<Cage>,
+    /// Dashed outlines of the cages, None without cages
+    cage_mesh: Option<Mesh>,
+    cage_labels: Vec<(Text, Vec2)>,
     grid_mesh: Mesh,
+    cell_fill_mesh: Mesh,
     grid_mesh_selection: Mesh,
     region_mesh: Mesh,
     number_draw: [Text; 10],
+    note_draw: [Text; 9],
 }
 
 impl GameBoard {
-    pub fn init(ctx: &Context, x: f32, y: f32, difficulty: &Difficulty) -> GameBoard {
+    pub fn init(ctx: &Context, x: f32, y: f32, puzzle: &Puzzle) -> GameBoard {
         let grid_rect = (0..9)
             .map(|i| {
                 (0..9)
@@ -114,6 +327,18 @@ impl GameBoard {
             graphics::Color::WHITE,
         )
         .unwrap();
+        let cell_fill_mesh = Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            Rect {
+                x: 0.,
+                y: 0.,
+                w: GRID_DIMENSION.0,
+                h: GRID_DIMENSION.1,
+            },
+            graphics::Color::WHITE,
+        )
+        .unwrap();
         let region_mesh = Mesh::new_rectangle(
             ctx,
             graphics::DrawMode::Stroke(
@@ -131,7 +356,15 @@ impl GameBoard {
         )
         .unwrap();
 
-        let (numbers, number_state) = GameBoard::generate_sudoku(difficulty);
+        let numbers = puzzle.givens;
+        let mut number_state = [[Condition::Neutral; 9]; 9];
+        for i in 0..9 {
+            for j in 0..9 {
+                if numbers[i][j] != 0 {
+                    number_state[i][j] = Condition::PreDetermined;
+                }
+            }
+        }
 
         let number_draw = (0..10)
             .map(|i| {
@@ -149,19 +382,127 @@ impl GameBoard {
             .try_into()
             .unwrap();
 
+        let note_draw = (1..=9)
+            .map(|i| {
+                Text::new(graphics::TextFragment::new(i.to_string()).scale(11.))
+                    .set_layout(graphics::TextLayout::center())
+                    .to_owned()
+            })
+            .collect::<Vec<Text>>()
+            .try_into()
+            .unwrap();
+
         GameBoard {
             grid_rect,
             grid_mesh,
             grid_mesh_selection,
+            cell_fill_mesh,
             region_mesh,
             numbers,
+            solution: puzzle.solution,
             number_state,
+            notes: [[0; 9]; 9],
             number_draw,
+            note_draw,
             number_selected: 0,
+            hint_cells: Vec::new(),
+            hint_targets: Vec::new(),
+            cursor: (0, 0),
+            highlight_cursor_peers: false,
+            cages: Vec::new(),
+            cage_mesh: None,
+            cage_labels: Vec::new(),
         }
     }
 
+    /// Turns the board into a Killer board, outlining each cage with its sum in the corner
+    pub fn set_cages(&mut self, ctx: &Context, cages: Vec<Cage>) -> GameResult {
+        self.cage_labels = cages
+            .iter()
+            .map(|cage| {
+                let (i, j) = cage.label_cell();
+                (
+                    Text::new(graphics::TextFragment::new(cage.sum.to_string()).scale(10.)),
+                    Vec2::new(self.grid_rect[i][j].x + 2., self.grid_rect[i][j].y + 1.),
+                )
+            })
+            .collect();
+        self.cage_mesh = None;
+        let origin = self.grid_rect[0][0];
+        let point = |(x, y): (f32, f32)| {
+            Vec2::new(
+                origin.x + x * GRID_DIMENSION.0,
+                origin.y + y * GRID_DIMENSION.1,
+            )
+        };
+        let mut builder = MeshBuilder::new();
+        let mut empty = true;
+        for cage in cages.iter() {
+            for (from, to) in cage.outline(CAGE_INSET) {
+                let (from, to) = (point(from), point(to));
+                let length = from.distance(to);
+                let direction = (to - from) / length;
+                let mut start = 0.;
+                while start < length {
+                    let end = (start + CAGE_DASH).min(length);
+                    builder.line(
+                        &[from + direction * start, from + direction * end],
+                        1.,
+                        graphics::Color::new(0.7, 0.7, 0.7, 1.0),
+                    )?;
+                    empty = false;
+                    start += CAGE_DASH * 2.;
+                }
+            }
+        }
+        if !empty {
+            self.cage_mesh = Some(Mesh::from_data(ctx, builder.build()));
+        }
+        self.cages = cages;
+        Ok(())
+    }
+
+    /// Moves the cursor by whole cells, wrapping around the edges
+    pub fn move_cursor(&mut self, di: isize, dj: isize) {
+        let (i, j) = self.cursor;
+        self.cursor = (
+            (i as isize + di).rem_euclid(9) as usize,
+            (j as isize + dj).rem_euclid(9) as usize,
+        );
+    }
+
     pub fn draw(&mut self, canvas: &mut graphics::Canvas) -> GameResult {
+        if self.highlight_cursor_peers {
+            let (ci, cj) = self.cursor;
+            for i in 0..9 {
+                for j in 0..9 {
+                    if i == ci || j == cj || (i / 3 == ci / 3 && j / 3 == cj / 3) {
+                        canvas.draw(
+                            &self.cell_fill_mesh,
+                            graphics::DrawParam::default()
+                                .dest(Vec2::new(self.grid_rect[i][j].x, self.grid_rect[i][j].y))
+                                .color(graphics::Color::new(1.0, 0.6, 0.1, 0.15)),
+                        );
+                    }
+                }
+            }
+        }
+        for (cells, color) in [
+            (&self.hint_cells, graphics::Color::new(0.3, 0.3, 0.8, 0.35)),
+            (
+                &self.hint_targets,
+                graphics::Color::new(0.2, 0.8, 0.3, 0.45),
+            ),
+        ] {
+            for &(i, j) in cells.iter() {
+                canvas.draw(
+                    &self.cell_fill_mesh,
+                    graphics::DrawParam::default()
+                        .dest(Vec2::new(self.grid_rect[i][j].x, self.grid_rect[i][j].y))
+                        .color(color),
+                );
+            }
+        }
         for i in 0..9 {
             canvas.draw(
                 &self.region_mesh,
@@ -206,107 +547,151 @@ impl GameBoard {
                         ))
                         .color(color),
                 );
+
+                if self.numbers[i][j] == 0 {
+                    self.draw_notes(canvas, i, j);
+                }
             }
         }
+        let (i, j) = self.cursor;
+        canvas.draw(
+            &self.grid_mesh_selection,
+            graphics::DrawParam::default()
+                .dest(Vec2::new(self.grid_rect[i][j].x, self.grid_rect[i][j].y))
+                .z(6)
+                .color(graphics::Color::new(1.0, 0.6, 0.1, 1.0)),
+        );
+        if let Some(cage_mesh) = &self.cage_mesh {
+            canvas.draw(cage_mesh, graphics::DrawParam::default().z(7));
+        }
+        for (label, position) in self.cage_labels.iter() {
+            canvas.draw(
+                label,
+                graphics::DrawParam::default()
+                    .dest(*position)
+                    .color(graphics::Color::new(0.7, 0.7, 0.7, 1.0))
+                    .z(7),
+            );
+        }
         Ok(())
     }
 
-    fn generate_sudoku(difficulty: &Difficulty) -> ([[u8; 9]; 9], [[Condition; 9]; 9]) {
-        let mut numbers = [[0u8; 9]; 9];
-        GameBoard::solve_sudoku(&mut numbers);
-        let mut conditions = [[Condition::PreDetermined; 9]; 9];
-        let number_remove: usize = match difficulty {
-            Difficulty::None => 81,
-            Difficulty::Easy => 45,
-            Difficulty::Intermediate => 54,
-            Difficulty::Hard => 63,
-        };
-        let mut number_removed = Vec::new();
-
-        while number_removed.len() < number_remove {
-            let (i, j): (usize, usize) = {
-                let rand = rand::random::<usize>() % 81;
-                (rand / 9, rand % 9)
-            };
-            if numbers[i][j] == 0 {
+    /// Draws the pencil marks of a cell as a 3x3 mini grid
+    fn draw_notes(&self, canvas: &mut graphics::Canvas, i: usize, j: usize) {
+        for k in 0..9 {
+            if self.notes[i][j] & (1 << k) == 0 {
                 continue;
             }
-            let backup = numbers[i][j];
-            numbers[i][j] = 0;
-            conditions[i][j] = Condition::Neutral;
-            number_removed.push((i, j, backup));
-            if GameBoard::has_unique_solution(&numbers) {
-                let (i, j, backup) = number_removed.pop().unwrap();
-                numbers[i][j] = backup;
-                conditions[i][j] = Condition::PreDetermined;
-            }
+            let color = if k + 1 == self.number_selected as usize {
+                graphics::Color::CYAN
+            } else {
+                graphics::Color::new(0.7, 0.7, 0.7, 1.0)
+            };
+            canvas.draw(
+                &self.note_draw[k],
+                graphics::DrawParam::default()
+                    .dest(Vec2::new(
+                        self.grid_rect[i][j].x + ((k % 3) as f32 + 0.5) * GRID_DIMENSION.0 / 3.,
+                        self.grid_rect[i][j].y + ((k / 3) as f32 + 0.5) * GRID_DIMENSION.1 / 3.,
+                    ))
+                    .color(color),
+            );
         }
-        (numbers, conditions)
-    }
-
-    fn has_unique_solution(numbers: &[[u8; 9]; 9]) -> bool {
-        let mut numbers_temp = *numbers;
-        GameBoard::solve_sudoku(&mut numbers_temp) && numbers_temp == *numbers
     }
 
-    fn solve_sudoku(numbers: &mut [[u8; 9]; 9]) -> bool {
-        let (i, j) = if let Some(empty_cell) = GameBoard::find_empty(numbers) {
-            (empty_cell as usize / 9, empty_cell as usize % 9)
-        } else {
-            return true;
-        };
-        //print!("a");
-
-        for number in 1..=9 {
-            if GameBoard::check_valid(number, i, j, numbers) {
-                numbers[i][j] = number;
-                if GameBoard::solve_sudoku(numbers) {
-                    return true;
+    /// Marks the entries `mode` finds wrong and tells whether the puzzle is solved.
+    /// Solved means every cell filled without breaking a rule, whatever is shown.
+    pub fn check(&mut self, mode: CheckMode) -> bool {
+        let mut solved = true;
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.number_state[i][j] == Condition::PreDetermined {
+                    continue;
                 }
-                numbers[i][j] = 0;
+                let number = self.numbers[i][j];
+                let conflict = number != 0
+                    && !validator::check_valid_in_cages(number, i, j, &self.numbers, &self.cages);
+                if number == 0 || conflict {
+                    solved = false;
+                }
+                let wrong = match mode {
+                    CheckMode::Off => false,
+                    CheckMode::Conflicts => conflict,
+                    CheckMode::Solution => number != 0 && number != self.solution[i][j],
+                };
+                self.number_state[i][j] = if wrong {
+                    Condition::Wrong
+                } else {
+                    Condition::Neutral
+                };
             }
         }
-        false
+        solved
     }
 
-    fn find_empty(numbers: &[[u8; 9]; 9]) -> Option<u8> {
-        for i in 0..81 {
-            if numbers[i / 9][i % 9] == 0 {
-                return Some(i as u8);
-            }
+    /// Writes a digit and crosses it out of the notes in its row, column and region
+    pub fn place(&mut self, i: usize, j: usize, number: u8) {
+        self.numbers[i][j] = number;
+        if number == 0 {
+            return;
+        }
+        self.notes[i][j] = 0;
+        for k in 0..9 {
+            self.notes[i][k] &= !(1 << (number - 1));
+            self.notes[k][j] &= !(1 << (number - 1));
+            self.notes[i - i % 3 + k / 3][j - j % 3 + k % 3] &= !(1 << (number - 1));
         }
-        None
     }
 
-    /// true means fine, false means there is same number(s) horizontally, vertically, or in the same region
-    pub fn check_valid(number: u8, i: usize, j: usize, numbers: &[[u8; 9]; 9]) -> bool {
-        let start_i = i - i % 3;
-        let start_j = j - j % 3;
-        for k in 0..9 {
-            if numbers[start_i + k / 3][start_j + k % 3] == number
-                && !(start_i + k / 3 == i && start_j + k % 3 == j)
-            {
-                return false;
-            }
+    /// Cells whose number or notes differ from the given snapshot
+    pub fn changes_since(&self, numbers: &Grid, notes: &[[u16; 9]; 9]) -> Vec<CellChange> {
+        (0..81)
+            .map(|k| (k / 9, k % 9))
+            .filter(|&(i, j)| {
+                numbers[i][j] != self.numbers[i][j] || notes[i][j] != self.notes[i][j]
+            })
+            .map(|(i, j)| CellChange {
+                i,
+                j,
+                number: (numbers[i][j], self.numbers[i][j]),
+                notes: (notes[i][j], self.notes[i][j]),
+            })
+            .collect()
+    }
 
-            if numbers[i][k] == number && j != k {
-                return false;
-            }
+    /// Sets the cells to how they were after the changes, or before them when `undo`
+    pub fn apply_changes(&mut self, changes: &[CellChange], undo: bool) {
+        for change in changes {
+            let (number, notes) = if undo {
+                (change.number.0, change.notes.0)
+            } else {
+                (change.number.1, change.notes.1)
+            };
+            self.numbers[change.i][change.j] = number;
+            self.notes[change.i][change.j] = notes;
+        }
+    }
 
-            if numbers[k][j] == number && i != k {
-                return false;
-            }
+    /// Adds or removes a pencil mark, 0 clears every mark of the cell
+    pub fn toggle_note(&mut self, i: usize, j: usize, number: u8) {
+        if number == 0 {
+            self.notes[i][j] = 0;
+        } else {
+            self.notes[i][j] ^= 1 << (number - 1);
         }
-        true
     }
 }
 
 pub struct NumberBoard {
     pub rect: [Rect; 10],
+    pub notes_rect: Rect,
     mesh: Mesh,
     mesh_selection: Mesh,
+    notes_mesh: Mesh,
     numbers: [Text; 10],
+    notes_text: Text,
     pub number_selection: u8,
+    pub notes_mode: bool,
 }
 
 impl NumberBoard {
@@ -363,12 +748,32 @@ impl NumberBoard {
             .try_into()
             .unwrap();
 
+        let notes_rect = Rect::new(x, y + 5. * GRID_DIMENSION.1, GRID_DIMENSION.0 * 2., 30.);
+        let notes_mesh = Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::Stroke(
+                graphics::StrokeOptions::default()
+                    .with_line_width(1.)
+                    .with_line_join(graphics::LineJoin::Bevel),
+            ),
+            Rect::new(0., 0., notes_rect.w, notes_rect.h),
+            graphics::Color::WHITE,
+        )
+        .unwrap();
+        let notes_text = Text::new(graphics::TextFragment::new("Notes").scale(17.))
+            .set_layout(graphics::TextLayout::center())
+            .to_owned();
+
         NumberBoard {
             rect,
+            notes_rect,
             mesh,
             mesh_selection,
+            notes_mesh,
             numbers,
+            notes_text,
             number_selection: 0,
+            notes_mode: false,
         }
     }
 
@@ -403,6 +808,27 @@ impl NumberBoard {
                 );
             }
         }
+
+        let color = if self.notes_mode {
+            graphics::Color::CYAN
+        } else {
+            graphics::Color::WHITE
+        };
+        canvas.draw(
+            &self.notes_mesh,
+            graphics::DrawParam::default()
+                .dest(Vec2::new(self.notes_rect.x, self.notes_rect.y))
+                .color(color),
+        );
+        canvas.draw(
+            &self.notes_text,
+            graphics::DrawParam::default()
+                .dest(Vec2::new(
+                    self.notes_rect.x + self.notes_rect.w / 2.,
+                    self.notes_rect.y + self.notes_rect.h / 2.,
+                ))
+                .color(color),
+        );
         Ok(())
     }
 }
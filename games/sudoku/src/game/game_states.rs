@@ -1,10 +1,15 @@
 use super::context::AddOnContext;
-use ggez::{event::MouseButton, graphics::Canvas, mint::Point2, Context, GameResult};
+use ggez::{
+    event::MouseButton, graphics::Canvas, input::keyboard::KeyInput, mint::Point2, Context,
+    GameResult,
+};
 
 pub mod leader_board;
 pub mod main_menu;
 pub mod playing;
+pub mod puzzle_library;
 pub mod select_difficulty;
+pub mod statistics;
 
 #[derive(Clone, Copy)]
 pub enum GameState {
@@ -12,6 +17,8 @@ pub enum GameState {
     SelectDifficulty,
     Playing,
     LeaderBoard,
+    PuzzleLibrary,
+    Statistics,
 }
 
 pub trait StateTrait {
@@ -27,4 +34,21 @@ pub trait StateTrait {
         _button: &MouseButton,
         _point: &Point2<f32>,
     ) -> GameResult;
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, _x: f32, _y: f32) -> GameResult {
+        Ok(())
+    }
+    fn key_down_event(&mut self, _ctx: &mut Context, _input: &KeyInput) -> GameResult {
+        Ok(())
+    }
+    fn text_input_event(&mut self, _ctx: &mut Context, _character: char) -> GameResult {
+        Ok(())
+    }
+    /// The window gained or lost focus
+    fn focus_event(&mut self, _ctx: &mut Context, _gained: bool) -> GameResult {
+        Ok(())
+    }
+    /// Called before the window closes
+    fn quit_event(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
 }
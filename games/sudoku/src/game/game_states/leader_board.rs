@@ -1,24 +1,108 @@
-use crate::game::{constants::SCREEN_SIZE, entity::Score, game_states::*, ui::*};
+use crate::game::{
+    constants::SCREEN_SIZE,
+    context::AddOnContext,
+    entity::{Difficulty, Score},
+    game_states::*,
+    saves,
+    ui::*,
+};
 use ggez::{
     glam::Vec2,
     graphics::{self, Mesh, Text},
+    input::keyboard::KeyCode,
     Context, GameResult,
 };
-use ron::de;
-use std::{collections::BTreeMap, fs};
+use std::collections::BTreeMap;
+use sudoku_core::daily;
+
+/// Rows visible at once
+const ROWS_PER_PAGE: usize = 12;
+
+/// What a tab lists, in tab order
+#[derive(Clone, Copy, PartialEq)]
+enum Tab {
+    Difficulty(Difficulty),
+    /// Today's daily puzzle
+    Daily,
+    /// Games played with a mistake limit, every difficulty together
+    Challenge,
+    /// Killer sudoku without a mistake limit
+    Killer(Difficulty),
+}
+
+const TABS: [Tab; 9] = [
+    Tab::Difficulty(Difficulty::Easy),
+    Tab::Difficulty(Difficulty::Intermediate),
+    Tab::Difficulty(Difficulty::Hard),
+    Tab::Difficulty(Difficulty::None),
+    Tab::Daily,
+    Tab::Challenge,
+    Tab::Killer(Difficulty::Easy),
+    Tab::Killer(Difficulty::Intermediate),
+    Tab::Killer(Difficulty::Hard),
+];
+
+/// Tabs on the top row, the Killer ones go on a row of their own below
+const TOP_ROW_TABS: usize = 6;
 
 pub struct LeaderBoard {
-    scores: Vec<Score>,
+    /// Scores of each tab, fastest first
+    lists: Vec<Vec<Score>>,
+    tab: usize,
+    /// First row shown
+    scroll: usize,
+    /// Tab and row of the score just saved by `Playing`
+    newest: Option<(usize, usize)>,
     texts: BTreeMap<&'static str, Text>,
+    tab_buttons: Vec<Button>,
     back_button: Button,
+    prev_button: Button,
+    next_button: Button,
     background: Mesh,
     change_state: Option<GameState>,
 }
 
 impl LeaderBoard {
-    pub fn new(ctx: &Context) -> Self {
-        let serialized = fs::read_to_string("./games/sudoku/saves/scores.ron").unwrap();
-        let scores: Vec<Score> = de::from_str(&serialized).unwrap();
+    pub fn new(ctx: &Context, addon_ctx: &mut AddOnContext) -> Self {
+        let (records, problem) = saves::load_records();
+        let scores = records.scores;
+        let today = daily::today();
+        let streak = daily::streak(scores.iter().filter_map(|score| score.daily), today);
+        let lists: Vec<Vec<Score>> = TABS
+            .iter()
+            .map(|tab| {
+                let mut list: Vec<Score> = scores
+                    .iter()
+                    .filter(|score| match tab {
+                        Tab::Difficulty(difficulty) => {
+                            score.daily.is_none()
+                                && score.mistake_limit.is_none()
+                                && !score.killer
+                                && score.difficulty == *difficulty
+                        }
+                        Tab::Daily => score.daily == Some(today),
+                        Tab::Challenge => score.mistake_limit.is_some(),
+                        Tab::Killer(difficulty) => {
+                            score.killer
+                                && score.mistake_limit.is_none()
+                                && score.difficulty == *difficulty
+                        }
+                    })
+                    .cloned()
+                    .collect();
+                list.sort_by_key(|score| score.time);
+                list
+            })
+            .collect();
+
+        let newest = addon_ctx.new_score.take().and_then(|new_score| {
+            lists.iter().enumerate().find_map(|(tab, list)| {
+                list.iter()
+                    .position(|score| *score == new_score)
+                    .map(|row| (tab, row))
+            })
+        });
+
         let mut texts = BTreeMap::new();
         texts.insert(
             "0_Title",
@@ -40,6 +124,40 @@ impl LeaderBoard {
             .set_layout(graphics::TextLayout::center())
             .to_owned(),
         );
+        texts.insert(
+            "2_Daily",
+            Text::new(
+                graphics::TextFragment::new(format!(
+                    "Daily {}, streak: {} {}",
+                    daily::format_date(today),
+                    streak,
+                    if streak == 1 { "day" } else { "days" }
+                ))
+                .color(graphics::Color::WHITE)
+                .scale(16.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        texts.insert(
+            "3_Range",
+            Text::new("")
+                .set_scale(15.)
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+        );
+        if let Some(problem) = problem {
+            texts.insert(
+                "4_Problem",
+                Text::new(
+                    graphics::TextFragment::new(problem)
+                        .color(graphics::Color::RED)
+                        .scale(15.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            );
+        }
         let vertices = [
             graphics::Vertex {
                 position: [0., 0.],
@@ -75,24 +193,101 @@ impl LeaderBoard {
                 indices: &indices,
             },
         );
-        let back_button = Button::new(
-            ctx,
-            graphics::Rect::new(30., 420., 80., 30.),
+        let button_text = |label: &str, scale: f32| {
             graphics::Text::new(
-                graphics::TextFragment::new("Back")
+                graphics::TextFragment::new(label)
                     .color(graphics::Color::WHITE)
-                    .scale(20.),
+                    .scale(scale),
             )
             .set_layout(graphics::TextLayout::center())
-            .to_owned(),
+            .to_owned()
+        };
+        let tab_buttons = TABS
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                let label = match tab {
+                    Tab::Difficulty(difficulty) => difficulty.to_string(),
+                    Tab::Daily => String::from("Daily"),
+                    Tab::Challenge => String::from("Challenge"),
+                    Tab::Killer(difficulty) => format!("Killer {}", difficulty),
+                };
+                let rect = match index.checked_sub(TOP_ROW_TABS) {
+                    None => graphics::Rect::new(30. + index as f32 * 110., 76., 104., 24.),
+                    Some(column) => {
+                        graphics::Rect::new(144. + column as f32 * 146., 104., 140., 24.)
+                    }
+                };
+                Button::new(ctx, rect, button_text(&label, 14.))
+            })
+            .collect();
+        let back_button = Button::new(
+            ctx,
+            graphics::Rect::new(30., 420., 80., 30.),
+            button_text("Back", 20.),
+        );
+        let prev_button = Button::new(
+            ctx,
+            graphics::Rect::new(450., 420., 70., 30.),
+            button_text("Prev", 18.),
         );
-        LeaderBoard {
-            scores,
+        let next_button = Button::new(
+            ctx,
+            graphics::Rect::new(530., 420., 70., 30.),
+            button_text("Next", 18.),
+        );
+
+        let mut leader_board = LeaderBoard {
+            lists,
+            tab: 0,
+            scroll: 0,
+            newest,
             texts,
+            tab_buttons,
             back_button,
+            prev_button,
+            next_button,
             background,
             change_state: None,
+        };
+        match newest {
+            Some((tab, row)) => {
+                leader_board.select_tab(tab);
+                leader_board.scroll_to(row.saturating_sub(ROWS_PER_PAGE / 2));
+            }
+            None => leader_board.select_tab(0),
+        }
+        leader_board
+    }
+
+    fn select_tab(&mut self, tab: usize) {
+        self.tab = tab;
+        for (index, button) in self.tab_buttons.iter_mut().enumerate() {
+            button.focused = index == tab;
         }
+        self.scroll_to(0);
+    }
+
+    /// Scrolls so `row` is on top, as far as the list allows
+    fn scroll_to(&mut self, row: usize) {
+        let len = self.lists[self.tab].len();
+        self.scroll = row.min(len.saturating_sub(ROWS_PER_PAGE));
+        let range = self.texts.get_mut("3_Range").unwrap();
+        range.clear();
+        if len == 0 {
+            range.add("No scores yet");
+        } else {
+            range.add(format!(
+                "{}-{} of {}",
+                self.scroll + 1,
+                (self.scroll + ROWS_PER_PAGE).min(len),
+                len
+            ));
+        }
+    }
+
+    fn scroll_by(&mut self, rows: isize) {
+        self.scroll_to(self.scroll.saturating_add_signed(rows));
     }
 }
 
@@ -112,25 +307,62 @@ impl StateTrait for LeaderBoard {
         canvas.draw(&self.background, graphics::DrawParam::default());
         for (key, text) in self.texts.iter() {
             match *key {
-                "0_Title" => canvas.draw(text, Vec2::new(360., 50.)),
+                "0_Title" => canvas.draw(text, Vec2::new(360., 45.)),
                 "1_Author" => canvas.draw(text, Vec2::new(640., 450.)),
+                "2_Daily" if TABS[self.tab] == Tab::Daily => {
+                    canvas.draw(text, Vec2::new(360., 140.))
+                }
+                "3_Range" => canvas.draw(text, Vec2::new(360., 400.)),
+                "4_Problem" => canvas.draw(text, Vec2::new(360., 465.)),
                 _ => (),
             }
         }
-        for i in 0..self.scores.len() {
+        let list = &self.lists[self.tab];
+        for (row, score) in list
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(ROWS_PER_PAGE)
+        {
+            let color = if self.newest == Some((self.tab, row)) {
+                graphics::Color::YELLOW
+            } else {
+                graphics::Color::WHITE
+            };
             canvas.draw(
-                &Text::new((i + 1).to_string())
-                    .add(". ")
-                    .add(self.scores[i].name.clone())
-                    .add(" ")
-                    .add(self.scores[i].difficulty)
-                    .add(" ")
-                    .add(TimeUI::format_common(&self.scores[i].time))
-                    .to_owned(),
-                Vec2::new(30., 20. * i as f32 + 100.),
+                &Text::new(
+                    graphics::TextFragment::new(format!(
+                        "{}. {} {}{}{}",
+                        row + 1,
+                        score.name,
+                        TimeUI::format_common(&score.time),
+                        match score.hints {
+                            0 => String::new(),
+                            1 => String::from(" (1 hint)"),
+                            hints => format!(" ({} hints)", hints),
+                        },
+                        match (score.mistake_limit, score.killer) {
+                            (Some(limit), killer) => format!(
+                                " {}{}, {}/{} mistakes",
+                                if killer { "Killer " } else { "" },
+                                score.difficulty,
+                                score.mistakes,
+                                limit
+                            ),
+                            (None, _) => String::new(),
+                        }
+                    ))
+                    .color(color),
+                ),
+                Vec2::new(200., 20. * (row - self.scroll) as f32 + 155.),
             );
         }
+        for button in self.tab_buttons.iter_mut() {
+            button.draw(canvas);
+        }
         self.back_button.draw(canvas);
+        self.prev_button.draw(canvas);
+        self.next_button.draw(canvas);
         Ok(())
     }
 
@@ -143,6 +375,51 @@ impl StateTrait for LeaderBoard {
         if self.back_button.rect.contains(*point) {
             self.change_state = Some(GameState::MainMenu);
         }
+        if self.prev_button.rect.contains(*point) {
+            self.scroll_by(-(ROWS_PER_PAGE as isize));
+        }
+        if self.next_button.rect.contains(*point) {
+            self.scroll_by(ROWS_PER_PAGE as isize);
+        }
+        let clicked = self
+            .tab_buttons
+            .iter()
+            .position(|button| button.rect.contains(*point));
+        if let Some(tab) = clicked {
+            self.select_tab(tab);
+        }
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, _x: f32, y: f32) -> GameResult {
+        // Wheel up scrolls toward the top of the list
+        if y > 0. {
+            self.scroll_by(-1);
+        } else if y < 0. {
+            self.scroll_by(1);
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: &KeyInput) -> GameResult {
+        match input.keycode {
+            Some(KeyCode::Escape | KeyCode::Return | KeyCode::NumpadEnter) => {
+                self.change_state = Some(GameState::MainMenu);
+            }
+            Some(KeyCode::Left | KeyCode::A) => {
+                self.select_tab((self.tab + TABS.len() - 1) % TABS.len())
+            }
+            Some(KeyCode::Right | KeyCode::D | KeyCode::Tab) => {
+                self.select_tab((self.tab + 1) % TABS.len())
+            }
+            Some(KeyCode::Up | KeyCode::W) => self.scroll_by(-1),
+            Some(KeyCode::Down | KeyCode::S) => self.scroll_by(1),
+            Some(KeyCode::PageUp) => self.scroll_by(-(ROWS_PER_PAGE as isize)),
+            Some(KeyCode::PageDown) => self.scroll_by(ROWS_PER_PAGE as isize),
+            Some(KeyCode::Home) => self.scroll_to(0),
+            Some(KeyCode::End) => self.scroll_to(usize::MAX),
+            _ => (),
+        }
         Ok(())
     }
 }
@@ -3,14 +3,21 @@ use std::collections::BTreeMap;
 use ggez::{
     glam::Vec2,
     graphics::{self, Color, Rect, Text},
+    input::keyboard::KeyCode,
     Context, GameResult,
 };
 
-use crate::game::{constants::*, game_states::*, ui::*};
+use crate::game::{constants::*, entity::SavedGame, game_states::*, saves, ui::*};
+use sudoku_core::daily;
 
 pub struct MainMenu {
     texts: BTreeMap<&'static str, Text>,
     buttons: BTreeMap<&'static str, Button>,
+    saved_game: Option<SavedGame>,
+    /// Set when Daily was picked
+    daily: Option<u32>,
+    /// Button pressed once while there is a saved game, pressing it again goes ahead
+    confirming: Option<&'static str>,
     background: graphics::Mesh,
     change_state: Option<GameState>,
 }
@@ -38,12 +45,43 @@ impl MainMenu {
             .set_layout(graphics::TextLayout::center())
             .to_owned(),
         );
+        let (saved_game, problem) = saves::load_game();
+        if let Some(problem) = problem {
+            texts.insert(
+                "2_Problem",
+                Text::new(
+                    graphics::TextFragment::new(problem)
+                        .color(Color::RED)
+                        .scale(15.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            );
+        }
+        // Everything moves down a row when Continue is shown
+        let offset = if saved_game.is_some() { 50. } else { 0. };
         let mut buttons = BTreeMap::new();
+        if saved_game.is_some() {
+            buttons.insert(
+                "0_Continue",
+                Button::new(
+                    ctx,
+                    Rect::new(300., 160., 120., 40.),
+                    Text::new(
+                        graphics::TextFragment::new("CONTINUE")
+                            .color(Color::WHITE)
+                            .scale(20.),
+                    )
+                    .set_layout(graphics::TextLayout::center())
+                    .to_owned(),
+                ),
+            );
+        }
         buttons.insert(
-            "Play",
+            "1_Play",
             Button::new(
                 ctx,
-                Rect::new(320., 200., 80., 40.),
+                Rect::new(320., 160. + offset, 80., 40.),
                 Text::new(
                     graphics::TextFragment::new("PLAY")
                         .color(Color::WHITE)
@@ -54,10 +92,24 @@ impl MainMenu {
             ),
         );
         buttons.insert(
-            "LeaderBoard",
+            "2_Daily",
             Button::new(
                 ctx,
-                Rect::new(280., 260., 160., 40.),
+                Rect::new(320., 210. + offset, 80., 40.),
+                Text::new(
+                    graphics::TextFragment::new("DAILY")
+                        .color(Color::WHITE)
+                        .scale(20.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            ),
+        );
+        buttons.insert(
+            "3_LeaderBoard",
+            Button::new(
+                ctx,
+                Rect::new(280., 260. + offset, 160., 40.),
                 Text::new(
                     graphics::TextFragment::new("LEADERBOARD")
                         .color(Color::WHITE)
@@ -68,10 +120,24 @@ impl MainMenu {
             ),
         );
         buttons.insert(
-            "Exit",
+            "4_Statistics",
+            Button::new(
+                ctx,
+                Rect::new(280., 310. + offset, 160., 40.),
+                Text::new(
+                    graphics::TextFragment::new("STATISTICS")
+                        .color(Color::WHITE)
+                        .scale(20.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            ),
+        );
+        buttons.insert(
+            "5_Exit",
             Button::new(
                 ctx,
-                Rect::new(320., 320., 80., 40.),
+                Rect::new(320., 360. + offset, 80., 40.),
                 Text::new(
                     graphics::TextFragment::new("EXIT")
                         .color(Color::WHITE)
@@ -119,19 +185,65 @@ impl MainMenu {
         MainMenu {
             texts,
             buttons,
+            saved_game,
+            daily: None,
+            confirming: None,
             background,
             change_state: None,
         }
     }
 }
 
+impl MainMenu {
+    fn press(&mut self, ctx: &mut Context, key: &'static str) {
+        // A new game takes the only save slot once it is left, so ask first
+        let new_game = matches!(key, "1_Play" | "2_Daily");
+        if new_game && self.saved_game.is_some() && self.confirming != Some(key) {
+            self.confirming = Some(key);
+            self.texts.insert(
+                "3_Confirm",
+                Text::new(
+                    graphics::TextFragment::new(
+                        "A new game replaces your unfinished one, press again to start it",
+                    )
+                    .color(Color::YELLOW)
+                    .scale(15.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            );
+            return;
+        }
+        self.confirming = None;
+        self.texts.remove("3_Confirm");
+        match key {
+            "0_Continue" => self.change_state = Some(GameState::Playing),
+            "1_Play" => self.change_state = Some(GameState::SelectDifficulty),
+            "2_Daily" => {
+                self.daily = Some(daily::today());
+                self.change_state = Some(GameState::Playing);
+            }
+            "3_LeaderBoard" => self.change_state = Some(GameState::LeaderBoard),
+            "4_Statistics" => self.change_state = Some(GameState::Statistics),
+            "5_Exit" => ctx.request_quit(),
+            _ => (),
+        }
+    }
+}
+
 impl StateTrait for MainMenu {
     fn update(
         &mut self,
         _ctx: &Context,
-        _addon_ctx: &mut AddOnContext,
+        addon_ctx: &mut AddOnContext,
     ) -> GameResult<Option<GameState>> {
         if let Some(new_state) = self.change_state {
+            if let Some(day) = self.daily {
+                addon_ctx.puzzle_code = Some(daily::code(day));
+                addon_ctx.daily = Some(day);
+            } else if matches!(new_state, GameState::Playing) {
+                addon_ctx.saved_game = self.saved_game.take();
+            }
             self.change_state = None;
             return Ok(Some(new_state));
         }
@@ -149,6 +261,8 @@ impl StateTrait for MainMenu {
             match *key {
                 "0_Title" => canvas.draw(text, Vec2::new(360., 100.)),
                 "1_Author" => canvas.draw(text, Vec2::new(640., 450.)),
+                "2_Problem" => canvas.draw(text, Vec2::new(360., 20.)),
+                "3_Confirm" => canvas.draw(text, Vec2::new(360., 145.)),
                 _ => (),
             }
         }
@@ -162,16 +276,24 @@ impl StateTrait for MainMenu {
         button: &MouseButton,
         point: &Point2<f32>,
     ) -> GameResult {
-        for (key, buttonui) in self.buttons.iter_mut() {
+        let mut pressed = None;
+        for (key, buttonui) in self.buttons.iter() {
             if buttonui.rect.contains(*point) && *button == MouseButton::Left {
-                match *key {
-                    "Play" => self.change_state = Some(GameState::SelectDifficulty),
-                    "LeaderBoard" => self.change_state = Some(GameState::LeaderBoard),
-                    "Exit" => ctx.request_quit(),
-                    _ => (),
-                }
+                pressed = Some(*key);
             }
         }
+        if let Some(key) = pressed {
+            self.press(ctx, key);
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, input: &KeyInput) -> GameResult {
+        if input.keycode == Some(KeyCode::Escape) {
+            ctx.request_quit();
+        } else if let Some(key) = navigate_buttons(&mut self.buttons, input) {
+            self.press(ctx, key);
+        }
         Ok(())
     }
 }
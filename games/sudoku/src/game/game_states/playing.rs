@@ -1,25 +1,86 @@
-use ggez::{graphics, Context, GameResult};
+use ggez::{
+    glam::Vec2,
+    graphics,
+    input::keyboard::{KeyCode, KeyInput, KeyMods},
+    Context, GameError, GameResult,
+};
 
-use crate::game::{constants::*, context, entity::*, game_states::*, ui::*};
+use crate::game::{constants::*, context, entity::*, game_states::*, saves, ui::*};
 
-use ron::{de, ser};
-use std::{fs, io::Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sudoku_core::{
+    daily, export,
+    logic::{self, LogicSolver},
+    solver, validator, Puzzle, PuzzleCode,
+};
 
 pub struct Playing {
     game_board: GameBoard,
     number_board: NumberBoard,
     back_button: Button,
+    hint_button: Button,
+    undo_button: Button,
+    redo_button: Button,
+    export_button: Button,
+    progress_button: Button,
+    solution_button: Button,
+    /// Whether exports include the entries made so far
+    export_progress: bool,
+    /// Whether exports include the solution
+    export_solution: bool,
+    export_text: graphics::Text,
+    input_mode_button: Button,
+    pause_button: Button,
+    /// Hides the board while paused
+    pause_cover: graphics::Mesh,
+    pause_text: graphics::Text,
+    paused: bool,
+    settings: Settings,
+    history: History,
+    hint_text: graphics::Text,
+    /// Eliminations from earlier hints, so the next hint moves on
+    hint_eliminations: Vec<(usize, usize, u8)>,
+    hints_used: u32,
+    /// The givens allow one solution only, so entries can be held against it. Free play
+    /// and ambiguous imports have a solution that is just one of many.
+    solution_known: bool,
+    /// Digits placed that didn't match the solution
+    mistakes: u32,
+    /// Strikes allowed in a challenge, None for a normal game
+    mistake_limit: Option<u32>,
+    mistakes_text: graphics::Text,
+    /// The challenge ran out of strikes
+    lost: bool,
+    check_mode_button: Button,
+    /// The most telling check shown so far, recorded with the score
+    check_mode_used: CheckMode,
+    lost_text: graphics::Text,
     background: graphics::Mesh,
     number_selection: u8,
     difficulty: Difficulty,
+    puzzle: Puzzle,
+    puzzle_code: Option<PuzzleCode>,
+    /// Day number when playing the daily puzzle
+    daily: Option<u32>,
+    code_text: graphics::Text,
     time: TimeUI,
-    scores: Vec<Score>,
+    /// Score of a finished puzzle, written once the player names it
+    pending_score: Option<Score>,
+    /// Saved score, handed to the leaderboard to highlight
+    new_score: Option<Score>,
+    name_panel: graphics::Mesh,
+    name_text: graphics::Text,
+    name_input: TextInput,
+    /// Set when the game ends, the name field takes focus on the next update so the
+    /// character of the keystroke that finished the puzzle doesn't land in it
+    focus_name_input: bool,
+    name_button: Button,
     gameover: bool,
     change_state: Option<GameState>,
 }
 
 impl Playing {
-    pub fn new(ctx: &Context, addon_ctx: &context::AddOnContext) -> Self {
+    pub fn new(ctx: &Context, addon_ctx: &mut context::AddOnContext) -> Self {
         let vertices = [
             graphics::Vertex {
                 position: [0., 0.],
@@ -68,74 +129,710 @@ impl Playing {
             .to_owned(),
         );
 
-        let serialized = fs::read_to_string("./games/sudoku/saves/scores.ron").unwrap();
-        let scores: Vec<Score> = de::from_str(&serialized).unwrap();
+        let hint_button = Button::new(
+            ctx,
+            graphics::Rect::new(60., 340., 80., 30.),
+            graphics::Text::new(
+                graphics::TextFragment::new("Hint")
+                    .color(graphics::Color::WHITE)
+                    .scale(20.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let undo_button = Button::new(
+            ctx,
+            graphics::Rect::new(60., 300., 38., 30.),
+            graphics::Text::new(
+                graphics::TextFragment::new("Undo")
+                    .color(graphics::Color::WHITE)
+                    .scale(14.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let redo_button = Button::new(
+            ctx,
+            graphics::Rect::new(102., 300., 38., 30.),
+            graphics::Text::new(
+                graphics::TextFragment::new("Redo")
+                    .color(graphics::Color::WHITE)
+                    .scale(14.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let export_button = Button::new(
+            ctx,
+            graphics::Rect::new(560., 60., 120., 30.),
+            graphics::Text::new(
+                graphics::TextFragment::new("Export")
+                    .color(graphics::Color::WHITE)
+                    .scale(18.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let progress_button = Button::new(
+            ctx,
+            graphics::Rect::new(560., 100., 120., 30.),
+            Playing::toggle_text("Progress", true),
+        );
+        let solution_button = Button::new(
+            ctx,
+            graphics::Rect::new(560., 140., 120., 30.),
+            Playing::toggle_text("Solution", false),
+        );
+        let export_text = graphics::Text::new("")
+            .set_scale(14.)
+            .set_bounds(Vec2::new(SCREEN_SIZE.0 - 570., 80.))
+            .set_wrap(true)
+            .to_owned();
+        let name_panel = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(220., 170., 280., 150.),
+            graphics::Color::new(0.05, 0., 0.08, 0.95),
+        )
+        .unwrap();
+        let name_text = graphics::Text::new(
+            graphics::TextFragment::new("Solved! Enter your name")
+                .color(graphics::Color::WHITE)
+                .scale(20.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned();
+        let name_input = TextInput::new(ctx, graphics::Rect::new(260., 215., 200., 30.), 16);
+        let name_button = Button::new(
+            ctx,
+            graphics::Rect::new(310., 265., 100., 30.),
+            graphics::Text::new(
+                graphics::TextFragment::new("Save")
+                    .color(graphics::Color::WHITE)
+                    .scale(18.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let pause_button = Button::new(
+            ctx,
+            graphics::Rect::new(560., 340., 120., 30.),
+            graphics::Text::new(
+                graphics::TextFragment::new("Pause")
+                    .color(graphics::Color::WHITE)
+                    .scale(18.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let pause_cover = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(175., 55., 370., 370.),
+            graphics::Color::new(0.05, 0., 0.08, 1.),
+        )
+        .unwrap();
+        let pause_text = graphics::Text::new(
+            graphics::TextFragment::new("PAUSED")
+                .color(graphics::Color::WHITE)
+                .scale(40.),
+        )
+        .add(
+            graphics::TextFragment::new("\nClick or press Enter to resume, Esc to leave")
+                .color(graphics::Color::WHITE)
+                .scale(16.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned();
+        let (settings, settings_problem) = saves::load_settings();
+        let check_mode_button = Button::new(
+            ctx,
+            graphics::Rect::new(560., 380., 120., 30.),
+            Playing::check_mode_text(settings.check_mode),
+        );
+        let input_mode_button = Button::new(
+            ctx,
+            graphics::Rect::new(560., 300., 120., 30.),
+            Playing::input_mode_text(settings.input_mode),
+        );
+        let mut hint_text = graphics::Text::new("")
+            .set_scale(16.)
+            .set_bounds(Vec2::new(SCREEN_SIZE.0 - 200., 40.))
+            .set_wrap(true)
+            .to_owned();
+
+        let lost_text = graphics::Text::new(
+            graphics::TextFragment::new("Out of mistakes")
+                .color(graphics::Color::RED)
+                .scale(28.),
+        )
+        .add(
+            graphics::TextFragment::new("\nPress Esc or Back to leave")
+                .color(graphics::Color::WHITE)
+                .scale(16.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned();
 
-        Playing {
-            game_board: GameBoard::init(ctx, 180., 60., &addon_ctx.difficulty.unwrap()),
+        let saved_game = addon_ctx.saved_game.take();
+        let imported = addon_ctx.imported.take();
+        let daily = match (&saved_game, imported) {
+            (Some(saved), _) => saved.daily,
+            (None, Some(_)) => None,
+            (None, None) => addon_ctx.daily,
+        };
+        let (puzzle_code, puzzle, cages) = match (&saved_game, imported) {
+            (Some(saved), _) => (saved.puzzle_code, saved.puzzle(), saved.cages.clone()),
+            (None, Some(imported)) => (None, imported.puzzle().unwrap(), Vec::new()),
+            (None, None) => {
+                let puzzle_code = addon_ctx.puzzle_code.unwrap_or_else(|| PuzzleCode {
+                    killer: settings.killer,
+                    ..PuzzleCode::random(addon_ctx.difficulty.unwrap())
+                });
+                if puzzle_code.killer {
+                    let killer = puzzle_code.generate_killer();
+                    (Some(puzzle_code), killer.puzzle, killer.cages)
+                } else {
+                    (
+                        Some(puzzle_code),
+                        puzzle_code.generate_with(&settings.solver),
+                        Vec::new(),
+                    )
+                }
+            }
+        };
+        // Generated cages always leave one solution
+        let solution_known = !cages.is_empty() || solver::has_unique_solution(&puzzle.givens);
+        let wanted_limit = match &saved_game {
+            Some(saved) => saved.mistake_limit,
+            // The daily is the same plain game for everyone
+            None if daily.is_some() => None,
+            None => settings.mistake_limit,
+        };
+        let mistake_limit = wanted_limit.filter(|_| solution_known);
+        let difficulty = match puzzle_code {
+            Some(puzzle_code) => puzzle_code.difficulty,
+            None => logic::rate(&puzzle.givens).difficulty(),
+        };
+        let code_text = match (daily, puzzle_code) {
+            (Some(day), _) => graphics::Text::new("Daily ")
+                .add(daily::format_date(day))
+                .set_scale(20.)
+                .to_owned(),
+            (None, Some(puzzle_code)) => graphics::Text::new("Code: ")
+                .add(puzzle_code.to_string())
+                .set_scale(20.)
+                .to_owned(),
+            (None, None) => graphics::Text::new("Imported").set_scale(20.).to_owned(),
+        };
+        if let Some(warning) = imported.and_then(|imported| imported.warning()) {
+            hint_text.add(warning);
+        }
+
+        let mut playing = Playing {
+            game_board: GameBoard::init(ctx, 180., 60., &puzzle),
             number_board: NumberBoard::init(ctx, 60., 60.),
             back_button,
+            hint_button,
+            undo_button,
+            redo_button,
+            export_button,
+            progress_button,
+            solution_button,
+            export_progress: true,
+            export_solution: false,
+            export_text,
+            input_mode_button,
+            pause_button,
+            pause_cover,
+            pause_text,
+            paused: false,
+            settings,
+            history: History::default(),
+            hint_text,
+            hint_eliminations: Vec::new(),
+            hints_used: 0,
+            solution_known,
+            mistakes: 0,
+            mistake_limit,
+            mistakes_text: graphics::Text::new("").set_scale(20.).to_owned(),
+            lost: false,
+            lost_text,
+            check_mode_button,
+            check_mode_used: CheckMode::Off,
             background,
             number_selection: 0,
-            difficulty: addon_ctx.difficulty.unwrap(),
+            difficulty,
+            puzzle,
+            puzzle_code,
+            daily,
+            code_text,
             time: TimeUI::new(),
-            scores,
+            pending_score: None,
+            new_score: None,
+            name_panel,
+            name_text,
+            name_input,
+            focus_name_input: false,
+            name_button,
             gameover: false,
             change_state: None,
+        };
+        if let Some(problem) = settings_problem {
+            playing.report(Err(GameError::CustomError(problem)));
+        }
+        if wanted_limit.is_some() && mistake_limit.is_none() {
+            playing.set_hint_text("No challenge here, this puzzle has more than one solution");
+        }
+        playing.check_mode_button.text = Playing::check_mode_text(playing.check_mode());
+        let outlined = playing.game_board.set_cages(ctx, cages);
+        playing.report(outlined);
+        playing.game_board.highlight_cursor_peers =
+            playing.settings.input_mode == InputMode::CellFirst;
+        if let Some(saved) = saved_game {
+            playing.game_board.numbers = saved.numbers;
+            playing.game_board.notes = saved.notes;
+            playing.history = saved.history;
+            playing.difficulty = saved.difficulty;
+            playing.time = TimeUI::with_offset(saved.time);
+            playing.hints_used = saved.hints;
+            playing.mistakes = saved.mistakes;
+            playing.check_mode_used = saved.check_mode;
+            playing.update_state();
+        } else {
+            let started = saves::record_start(playing.difficulty);
+            playing.report(started);
         }
+        playing.update_mistakes_text();
+        playing
     }
 
-    fn update_state(&mut self) {
-        let mut gameover = true;
-        for i in 0..9 {
-            for j in 0..9 {
-                if !GameBoard::check_valid(
-                    self.game_board.numbers[i][j],
-                    i,
-                    j,
-                    &self.game_board.numbers,
-                ) && self.game_board.number_state[i][j] != Condition::PreDetermined
-                    && self.game_board.numbers[i][j] != 0
-                {
-                    self.game_board.number_state[i][j] = Condition::Wrong;
-                } else if self.game_board.number_state[i][j] == Condition::PreDetermined {
-                    self.game_board.number_state[i][j] = Condition::PreDetermined;
-                } else {
-                    self.game_board.number_state[i][j] = Condition::Neutral;
-                }
+    fn save_game(&self) -> GameResult {
+        saves::store_game(&SavedGame {
+            puzzle_code: self.puzzle_code,
+            difficulty: self.difficulty,
+            givens: self.puzzle.givens,
+            solution: self.puzzle.solution,
+            numbers: self.game_board.numbers,
+            notes: self.game_board.notes,
+            history: self.history.clone(),
+            time: self.time.elapsed(),
+            hints: self.hints_used,
+            daily: self.daily,
+            mistakes: self.mistakes,
+            mistake_limit: self.mistake_limit,
+            check_mode: self.check_mode_used,
+            cages: self.game_board.cages.clone(),
+        })
+    }
+
+    fn toggle_text(label: &str, on: bool) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(format!("{}: {}", label, if on { "On" } else { "Off" }))
+                .color(graphics::Color::WHITE)
+                .scale(16.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    fn input_mode_text(input_mode: InputMode) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(input_mode.to_string())
+                .color(graphics::Color::WHITE)
+                .scale(16.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    fn toggle_input_mode(&mut self) -> GameResult {
+        self.settings.input_mode = match self.settings.input_mode {
+            InputMode::DigitFirst => InputMode::CellFirst,
+            InputMode::CellFirst => InputMode::DigitFirst,
+        };
+        self.input_mode_button.text = Playing::input_mode_text(self.settings.input_mode);
+        self.game_board.highlight_cursor_peers = self.settings.input_mode == InputMode::CellFirst;
+        saves::store_settings(&self.settings)
+    }
+
+    fn check_mode_text(check_mode: CheckMode) -> graphics::Text {
+        graphics::Text::new(
+            graphics::TextFragment::new(format!("Check: {}", check_mode))
+                .color(graphics::Color::WHITE)
+                .scale(16.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    fn cycle_check_mode(&mut self) -> GameResult {
+        self.settings.check_mode = match self.settings.check_mode {
+            CheckMode::Off => CheckMode::Conflicts,
+            CheckMode::Conflicts => CheckMode::Solution,
+            CheckMode::Solution => CheckMode::Off,
+        };
+        // Without a single solution there is nothing to check against
+        if !self.solution_known && self.settings.check_mode == CheckMode::Solution {
+            self.settings.check_mode = CheckMode::Off;
+        }
+        self.check_mode_button.text = Playing::check_mode_text(self.check_mode());
+        self.update_state();
+        saves::store_settings(&self.settings)
+    }
+
+    /// Writes the givens, and the progress or solution if asked for, as text and SVG
+    fn export(&mut self) {
+        let stem = match self.puzzle_code {
+            Some(puzzle_code) => format!("sudoku-{}", puzzle_code),
+            None => format!(
+                "sudoku-imported-{}",
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            ),
+        };
+        let mut text = match self.puzzle_code {
+            Some(puzzle_code) => format!("# Sudoku {} ({})\n", puzzle_code, self.difficulty),
+            None => format!("# Sudoku ({})\n", self.difficulty),
+        };
+        if self.export_progress {
+            text += &format!(
+                "# Progress: {}\n",
+                export::to_line(&self.game_board.numbers)
+            );
+        }
+        if self.export_solution {
+            text += &format!("# Solution: {}\n", export::to_line(&self.puzzle.solution));
+        }
+        for cage in self.game_board.cages.iter() {
+            let cells: Vec<String> = cage
+                .cells
+                .iter()
+                .map(|&(i, j)| format!("r{}c{}", i + 1, j + 1))
+                .collect();
+            text += &format!("# Cage {}: {}\n", cage.sum, cells.join(" "));
+        }
+        text += &export::to_line(&self.puzzle.givens);
+        text.push('\n');
 
-                if gameover && self.game_board.number_state[i][j] == Condition::Wrong
-                    || self.game_board.numbers[i][j] == 0
+        let progress = self.export_progress.then_some(&self.game_board.numbers);
+        let mut files = vec![
+            (format!("{}.txt", stem), text),
+            (
+                format!("{}.svg", stem),
+                export::to_svg_with_cages(&self.puzzle.givens, progress, &self.game_board.cages),
+            ),
+        ];
+        if self.export_solution {
+            files.push((
+                format!("{}-solution.svg", stem),
+                export::to_svg_with_cages(
+                    &self.puzzle.givens,
+                    Some(&self.puzzle.solution),
+                    &self.game_board.cages,
+                ),
+            ));
+        }
+
+        self.export_text.clear();
+        match saves::store_export(&files) {
+            Ok(dir) => self
+                .export_text
+                .add(format!("Saved {} to {}", stem, dir.display())),
+            Err(e) => self.export_text.add(
+                graphics::TextFragment::new(format!("Export failed: {}", e))
+                    .color(graphics::Color::RED),
+            ),
+        };
+    }
+
+    /// Leaves to wherever the puzzle was picked, keeping an unfinished game to continue later
+    fn back(&mut self) -> GameResult {
+        if !self.gameover {
+            self.save_game()?;
+        }
+        if self.pending_score.is_some() {
+            return self.store_score(DEFAULT_PLAYER_NAME);
+        }
+        self.change_state = match (self.daily, self.puzzle_code) {
+            (Some(_), _) => Some(GameState::MainMenu),
+            (None, Some(_)) => Some(GameState::SelectDifficulty),
+            (None, None) => Some(GameState::PuzzleLibrary),
+        };
+        Ok(())
+    }
+
+    /// Stops the clock and covers the board, unless the game is already over
+    fn pause(&mut self) {
+        if self.gameover {
+            return;
+        }
+        self.paused = true;
+        self.time.pause();
+    }
+
+    fn resume(&mut self) {
+        self.paused = false;
+        self.time.resume();
+    }
+
+    fn select_number(&mut self, number: u8) {
+        self.number_selection = number;
+        self.number_board.number_selection = number;
+        self.game_board.number_selected = number;
+    }
+
+    /// Writes a digit, or a note in notes mode, unless the cell is a given
+    fn input_number(&mut self, i: usize, j: usize, number: u8) {
+        if self.game_board.number_state[i][j] == Condition::PreDetermined {
+            return;
+        }
+        if self.number_board.notes_mode {
+            if self.game_board.numbers[i][j] == 0 {
+                self.edit(|board| board.toggle_note(i, j, number));
+            }
+        } else {
+            let changed = number != self.game_board.numbers[i][j];
+            let mistake = self.solution_known
+                && changed
+                && number != 0
+                && number != self.game_board.solution[i][j];
+            self.edit(|board| board.place(i, j, number));
+            if mistake {
+                self.mistakes += 1;
+                self.update_mistakes_text();
+                if self
+                    .mistake_limit
+                    .is_some_and(|limit| self.mistakes >= limit)
                 {
-                    gameover = false;
+                    self.lose();
                 }
             }
         }
-        if gameover {
+    }
+
+    fn update_mistakes_text(&mut self) {
+        self.mistakes_text.clear();
+        if let Some(limit) = self.mistake_limit {
+            self.mistakes_text
+                .add(format!("Mistakes: {}/{}", self.mistakes, limit));
+        }
+    }
+
+    /// Ends a challenge once the strikes run out. Nothing is scored and the save is gone
+    fn lose(&mut self) {
+        self.gameover = true;
+        self.lost = true;
+        let deleted = saves::delete_game(self.puzzle_code, &self.puzzle.givens);
+        self.report(deleted);
+    }
+
+    /// Clears the digit of a cell, or its notes when it is already empty
+    fn clear_cell(&mut self, i: usize, j: usize) {
+        if self.game_board.number_state[i][j] == Condition::PreDetermined {
+            return;
+        }
+        if self.game_board.numbers[i][j] == 0 {
+            self.edit(|board| board.toggle_note(i, j, 0));
+        } else {
+            self.edit(|board| board.place(i, j, 0));
+        }
+    }
+
+    /// What entries are checked against. Challenges always use the solution, and
+    /// puzzles without a single solution only get their conflicts marked.
+    fn check_mode(&self) -> CheckMode {
+        match (self.mistake_limit, self.settings.check_mode) {
+            (Some(_), _) => CheckMode::Solution,
+            (None, CheckMode::Solution) if !self.solution_known => CheckMode::Conflicts,
+            (None, check_mode) => check_mode,
+        }
+    }
+
+    fn update_state(&mut self) {
+        let check_mode = self.check_mode();
+        self.check_mode_used = self.check_mode_used.max(check_mode);
+        if self.game_board.check(check_mode) {
             self.gameover();
         }
     }
 
+    /// Runs an edit on the board and records what it changed in the history
+    fn edit(&mut self, edit: impl FnOnce(&mut GameBoard)) {
+        let numbers = self.game_board.numbers;
+        let notes = self.game_board.notes;
+        edit(&mut self.game_board);
+        let changes = self.game_board.changes_since(&numbers, &notes);
+        if changes
+            .iter()
+            .any(|change| change.number.0 != change.number.1)
+        {
+            self.clear_hint();
+        }
+        self.history.record(changes);
+        self.update_state();
+    }
+
+    fn undo(&mut self) {
+        if let Some(changes) = self.history.undo() {
+            self.game_board.apply_changes(changes, true);
+            self.clear_hint();
+            self.update_state();
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(changes) = self.history.redo() {
+            self.game_board.apply_changes(changes, false);
+            self.clear_hint();
+            self.update_state();
+        }
+    }
+
+    /// The logical solver knows nothing of cage sums, so Killer games go without hints
+    fn hints_available(&self) -> bool {
+        self.game_board.cages.is_empty()
+    }
+
+    /// Shows the next logical step on the current board
+    fn hint(&mut self) {
+        self.game_board.hint_cells.clear();
+        self.game_board.hint_targets.clear();
+
+        // Rule conflicts are pointed out even when the check mode doesn't show them
+        let board = &self.game_board;
+        let conflicts: Vec<(usize, usize)> = (0..81)
+            .map(|k| (k / 9, k % 9))
+            .filter(|&(i, j)| {
+                let number = board.numbers[i][j];
+                board.number_state[i][j] == Condition::Wrong
+                    || board.number_state[i][j] != Condition::PreDetermined
+                        && number != 0
+                        && !validator::check_valid_in_cages(
+                            number,
+                            i,
+                            j,
+                            &board.numbers,
+                            &board.cages,
+                        )
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            self.set_hint_text("Clear the marked cells first, they are wrong");
+            self.game_board.hint_targets = conflicts;
+            return;
+        }
+
+        // A step worked out from a wrong entry could be wrong too. Showing them tells
+        // more than the check mode does, so it costs a hint and is recorded as a
+        // solution check.
+        let mismatches: Vec<(usize, usize)> = (0..81)
+            .map(|k| (k / 9, k % 9))
+            .filter(|&(i, j)| {
+                let number = board.numbers[i][j];
+                self.solution_known
+                    && board.number_state[i][j] != Condition::PreDetermined
+                    && number != 0
+                    && number != board.solution[i][j]
+            })
+            .collect();
+        if !mismatches.is_empty() {
+            self.hints_used += 1;
+            self.check_mode_used = CheckMode::Solution;
+            self.set_hint_text("Clear the marked cells first, they don't match the solution");
+            self.game_board.hint_targets = mismatches;
+            return;
+        }
+
+        let mut solver = LogicSolver::new(&self.game_board.numbers);
+        for &(i, j, number) in self.hint_eliminations.iter() {
+            solver.eliminate(i, j, number);
+        }
+        if let Some(step) = solver.next_step() {
+            self.hints_used += 1;
+            self.hint_eliminations.extend(step.eliminations.iter());
+            self.game_board.hint_cells = step.cells;
+            self.game_board.hint_targets = step
+                .placement
+                .iter()
+                .chain(step.eliminations.iter())
+                .map(|&(i, j, _)| (i, j))
+                .collect();
+            self.set_hint_text(&step.explanation);
+        } else {
+            self.set_hint_text("No logical step found from here");
+        }
+    }
+
+    fn set_hint_text(&mut self, text: &str) {
+        self.hint_text.clear();
+        self.hint_text.add(text);
+    }
+
+    /// Forgets the hint on show and the eliminations of earlier ones, which only hold
+    /// for the digits that were on the board when they were found
+    fn clear_hint(&mut self) {
+        self.hint_eliminations.clear();
+        self.game_board.hint_cells.clear();
+        self.game_board.hint_targets.clear();
+        self.set_hint_text("");
+    }
+
     fn gameover(&mut self) {
         self.gameover = true;
-        self.scores.push(Score::new(
-            "Something",
-            self.difficulty,
-            self.time.time.time_since_start(),
-        ));
-        self.scores.sort_by_key(|score| score.time.as_millis());
-
-        let serialized = ser::to_string_pretty(
-            &self.scores,
-            ser::PrettyConfig::default().struct_names(true),
-        )
-        .unwrap();
-        fs::File::create("./games/sudoku/saves/scores.ron")
-            .unwrap()
-            .write_all(serialized.as_bytes())
-            .unwrap();
+        let deleted = saves::delete_game(self.puzzle_code, &self.puzzle.givens);
+        self.report(deleted);
+        if self.puzzle_code.is_none() {
+            let recorded = saves::record_completion(&self.puzzle.givens, self.time.elapsed());
+            self.report(recorded);
+        }
+        self.pending_score = Some(Score {
+            daily: self.daily,
+            mistakes: self.mistakes,
+            mistake_limit: self.mistake_limit,
+            check_mode: self.check_mode_used,
+            killer: !self.game_board.cages.is_empty(),
+            ..Score::new(
+                DEFAULT_PLAYER_NAME,
+                self.difficulty,
+                self.time.elapsed(),
+                self.hints_used,
+            )
+        });
+        self.name_input.value = self.settings.player_name.clone().unwrap_or_default();
+        self.focus_name_input = true;
+    }
 
+    /// Writes the finished puzzle's score under `name`, or the default name when it's empty
+    fn store_score(&mut self, name: &str) -> GameResult {
+        let Some(mut score) = self.pending_score.clone() else {
+            return Ok(());
+        };
+        let name = name.trim();
+        if !name.is_empty() {
+            score.name = name.to_string();
+        }
+        saves::record_score(score.clone())?;
+        self.pending_score = None;
+        self.new_score = Some(score);
         self.change_state = Some(GameState::LeaderBoard);
+
+        if !name.is_empty() && self.settings.player_name.as_deref() != Some(name) {
+            self.settings.player_name = Some(name.to_string());
+            saves::store_settings(&self.settings)?;
+        }
+        Ok(())
+    }
+
+    /// Shows what went wrong where the hints go, instead of stopping the game
+    fn report(&mut self, result: GameResult) {
+        if let Err(e) = result {
+            self.hint_text.clear();
+            self.hint_text
+                .add(graphics::TextFragment::new(e.to_string()).color(graphics::Color::RED));
+        }
     }
 }
 
@@ -143,13 +840,18 @@ impl StateTrait for Playing {
     fn update(
         &mut self,
         _ctx: &Context,
-        _addon_ctx: &mut AddOnContext,
+        addon_ctx: &mut AddOnContext,
     ) -> GameResult<Option<GameState>> {
         if let Some(new_state) = self.change_state {
+            addon_ctx.new_score = self.new_score.take();
             return Ok(Some(new_state));
         }
 
-        if self.gameover {
+        if self.focus_name_input {
+            self.focus_name_input = false;
+            self.name_input.focused = true;
+        }
+        if self.gameover || self.paused {
             return Ok(None);
         }
 
@@ -163,7 +865,52 @@ impl StateTrait for Playing {
         self.game_board.draw(canvas)?;
         self.number_board.draw(canvas)?;
         self.back_button.draw(canvas);
+        if self.hints_available() {
+            self.hint_button.draw(canvas);
+        }
+        self.undo_button.draw(canvas);
+        self.redo_button.draw(canvas);
+        self.export_button.draw(canvas);
+        self.progress_button.draw(canvas);
+        self.solution_button.draw(canvas);
+        self.input_mode_button.draw(canvas);
+        self.pause_button.draw(canvas);
+        if self.mistake_limit.is_none() {
+            self.check_mode_button.draw(canvas);
+        }
+        if self.paused {
+            // Above everything the board draws, cursor included
+            canvas.draw(&self.pause_cover, graphics::DrawParam::default().z(10));
+            canvas.draw(
+                &self.pause_text,
+                graphics::DrawParam::default().dest([360., 240.]).z(10),
+            );
+        }
+        canvas.draw(&self.export_text, Vec2::new(560., 180.));
+        canvas.draw(&self.hint_text, Vec2::new(180., 428.));
         self.time.draw(canvas);
+        canvas.draw(&self.mistakes_text, Vec2::new(180., 20.));
+        canvas.draw(
+            &self.code_text,
+            graphics::DrawParam::default().dest([540., 20.]),
+        );
+        if self.lost {
+            canvas.draw(&self.name_panel, graphics::DrawParam::default().z(10));
+            canvas.draw(
+                &self.lost_text,
+                graphics::DrawParam::default().dest([360., 245.]).z(10),
+            );
+        }
+        if self.pending_score.is_some() {
+            // Above everything the board draws
+            canvas.draw(&self.name_panel, graphics::DrawParam::default().z(10));
+            canvas.draw(
+                &self.name_text,
+                graphics::DrawParam::default().dest([360., 192.]).z(10),
+            );
+            self.name_input.draw_z(canvas, 10);
+            self.name_button.draw_z(canvas, 10);
+        }
 
         Ok(())
     }
@@ -174,30 +921,93 @@ impl StateTrait for Playing {
         button: &MouseButton,
         point: &Point2<f32>,
     ) -> GameResult {
+        if self.pending_score.is_some() {
+            if *button == MouseButton::Left && self.name_button.rect.contains(*point) {
+                let name = self.name_input.value.clone();
+                let stored = self.store_score(&name);
+                self.report(stored);
+            }
+            return Ok(());
+        }
+        if self.paused {
+            if *button == MouseButton::Left && self.back_button.rect.contains(*point) {
+                let left = self.back();
+                self.report(left);
+            } else {
+                self.resume();
+            }
+            return Ok(());
+        }
         if *button == MouseButton::Left {
             if self.back_button.rect.contains(*point) {
-                self.change_state = Some(GameState::SelectDifficulty);
+                let left = self.back();
+                self.report(left);
+            }
+
+            if self.export_button.rect.contains(*point) {
+                self.export();
+            }
+            if self.progress_button.rect.contains(*point) {
+                self.export_progress = !self.export_progress;
+                self.progress_button.text = Playing::toggle_text("Progress", self.export_progress);
+            }
+            if self.solution_button.rect.contains(*point) {
+                self.export_solution = !self.export_solution;
+                self.solution_button.text = Playing::toggle_text("Solution", self.export_solution);
+            }
+
+            if self.input_mode_button.rect.contains(*point) {
+                let stored = self.toggle_input_mode();
+                self.report(stored);
             }
 
             if self.gameover {
                 return Ok(());
             }
 
-            for i in 0..10 {
-                if self.number_board.rect[i].contains(*point) {
-                    self.number_selection = i as u8;
-                    self.number_board.number_selection = self.number_selection;
-                    self.game_board.number_selected = self.number_selection;
+            if self.pause_button.rect.contains(*point) {
+                self.pause();
+                return Ok(());
+            }
+            if self.mistake_limit.is_none() && self.check_mode_button.rect.contains(*point) {
+                let stored = self.cycle_check_mode();
+                self.report(stored);
+            }
+            if self.hints_available() && self.hint_button.rect.contains(*point) {
+                self.hint();
+            }
+            if self.undo_button.rect.contains(*point) {
+                self.undo();
+            }
+            if self.redo_button.rect.contains(*point) {
+                self.redo();
+            }
+
+            if self.number_board.notes_rect.contains(*point) {
+                self.number_board.notes_mode = !self.number_board.notes_mode;
+            }
+
+            let cell_first = self.settings.input_mode == InputMode::CellFirst;
+            for number in 0..10 {
+                if self.number_board.rect[number].contains(*point) {
+                    self.select_number(number as u8);
+                    if cell_first {
+                        let (i, j) = self.game_board.cursor;
+                        match number {
+                            0 => self.clear_cell(i, j),
+                            _ => self.input_number(i, j, number as u8),
+                        }
+                    }
                 }
             }
 
             for i in 0..9 {
                 for j in 0..9 {
-                    if self.game_board.grid_rect[i][j].contains(*point)
-                        && self.game_board.numbers[i][j] == 0
-                    {
-                        self.game_board.numbers[i][j] = self.number_selection;
-                        self.update_state();
+                    if self.game_board.grid_rect[i][j].contains(*point) {
+                        self.game_board.cursor = (i, j);
+                        if !cell_first && self.game_board.numbers[i][j] == 0 {
+                            self.input_number(i, j, self.number_selection);
+                        }
                     }
                 }
             }
@@ -205,15 +1015,126 @@ impl StateTrait for Playing {
         if *button == MouseButton::Right {
             for i in 0..9 {
                 for j in 0..9 {
-                    if self.game_board.grid_rect[i][j].contains(*point)
-                        && self.game_board.number_state[i][j] != Condition::PreDetermined
-                    {
-                        self.game_board.numbers[i][j] = 0;
-                        self.update_state();
+                    if self.game_board.grid_rect[i][j].contains(*point) {
+                        self.clear_cell(i, j);
                     }
                 }
             }
         }
         Ok(())
     }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: &KeyInput) -> GameResult {
+        let Some(keycode) = input.keycode else {
+            return Ok(());
+        };
+        if self.pending_score.is_some() {
+            match keycode {
+                KeyCode::Back => self.name_input.delete(),
+                KeyCode::Return | KeyCode::NumpadEnter => {
+                    let name = self.name_input.value.clone();
+                    let stored = self.store_score(&name);
+                    self.report(stored);
+                }
+                KeyCode::Escape => {
+                    let stored = self.store_score(DEFAULT_PLAYER_NAME);
+                    self.report(stored);
+                }
+                _ => (),
+            }
+            return Ok(());
+        }
+        if self.paused {
+            match keycode {
+                KeyCode::Escape => {
+                    let left = self.back();
+                    self.report(left);
+                }
+                KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space | KeyCode::P => {
+                    self.resume()
+                }
+                _ => (),
+            }
+            return Ok(());
+        }
+        if self.gameover {
+            if keycode == KeyCode::Escape {
+                let left = self.back();
+                self.report(left);
+            }
+            return Ok(());
+        }
+        if let KeyCode::Escape | KeyCode::P = keycode {
+            self.pause();
+            return Ok(());
+        }
+        if input.mods.contains(KeyMods::CTRL) {
+            match keycode {
+                KeyCode::Z if input.mods.contains(KeyMods::SHIFT) => self.redo(),
+                KeyCode::Z => self.undo(),
+                KeyCode::Y => self.redo(),
+                _ => (),
+            }
+            return Ok(());
+        }
+
+        let (i, j) = self.game_board.cursor;
+        match keycode {
+            KeyCode::Up | KeyCode::W => self.game_board.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::S => self.game_board.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::A => self.game_board.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::D => self.game_board.move_cursor(0, 1),
+            KeyCode::N => self.number_board.notes_mode = !self.number_board.notes_mode,
+            KeyCode::Back | KeyCode::Delete | KeyCode::Key0 | KeyCode::Numpad0 => {
+                self.clear_cell(i, j)
+            }
+            _ => {
+                if let Some(number) = digit(keycode) {
+                    self.select_number(number);
+                    self.input_number(i, j, number);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        self.name_input.input(character);
+        Ok(())
+    }
+
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) -> GameResult {
+        if !gained {
+            self.pause();
+        }
+        Ok(())
+    }
+
+    fn quit_event(&mut self, _ctx: &mut Context) -> GameResult {
+        if !self.gameover {
+            self.save_game()?;
+        }
+        // A win isn't lost by closing the window before naming it
+        if self.pending_score.is_some() {
+            self.store_score(DEFAULT_PLAYER_NAME)?;
+        }
+        Ok(())
+    }
+}
+
+/// The digit on a number row or keypad key
+fn digit(keycode: KeyCode) -> Option<u8> {
+    let number = match keycode {
+        KeyCode::Key1 | KeyCode::Numpad1 => 1,
+        KeyCode::Key2 | KeyCode::Numpad2 => 2,
+        KeyCode::Key3 | KeyCode::Numpad3 => 3,
+        KeyCode::Key4 | KeyCode::Numpad4 => 4,
+        KeyCode::Key5 | KeyCode::Numpad5 => 5,
+        KeyCode::Key6 | KeyCode::Numpad6 => 6,
+        KeyCode::Key7 | KeyCode::Numpad7 => 7,
+        KeyCode::Key8 | KeyCode::Numpad8 => 8,
+        KeyCode::Key9 | KeyCode::Numpad9 => 9,
+        _ => return None,
+    };
+    Some(number)
 }
@@ -0,0 +1,360 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Color, Rect, Text, TextFragment},
+    input::keyboard::KeyCode,
+    Context, GameResult,
+};
+
+use crate::game::{constants::*, entity::Pack, game_states::*, saves, ui::*};
+use sudoku_core::{
+    export,
+    import::ImportedPuzzle,
+    logic::{self, Rating},
+};
+
+/// Puzzle rows shown at once
+const ROWS_PER_PAGE: usize = 8;
+
+pub struct PuzzleLibrary {
+    texts: BTreeMap<&'static str, Text>,
+    buttons: BTreeMap<&'static str, Button>,
+    /// One button per puzzle on the current page
+    rows: Vec<Button>,
+    packs: Vec<Pack>,
+    /// Same layout as `packs`
+    ratings: Vec<Vec<Rating>>,
+    completions: BTreeMap<String, Duration>,
+    pack_index: usize,
+    page: usize,
+    /// Puzzle in the current pack picked with the keyboard
+    cursor: Option<usize>,
+    background: graphics::Mesh,
+    selected: Option<ImportedPuzzle>,
+    change_state: Option<GameState>,
+}
+
+impl PuzzleLibrary {
+    pub fn new(ctx: &Context) -> Self {
+        let (settings, problem) = saves::load_settings();
+        let (packs, mut messages) = saves::load_packs(&settings.solver);
+        messages.extend(problem);
+        let (completions, problem) = saves::load_completions();
+        messages.extend(problem);
+        let ratings = packs
+            .iter()
+            .map(|pack| {
+                pack.puzzles
+                    .iter()
+                    .map(|puzzle| logic::rate(&puzzle.givens))
+                    .collect()
+            })
+            .collect();
+
+        let mut texts = BTreeMap::new();
+        texts.insert(
+            "0_Title",
+            Text::new(
+                graphics::TextFragment::new("Puzzle Library")
+                    .color(Color::WHITE)
+                    .scale(40.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        texts.insert(
+            "1_Pack",
+            Text::new("")
+                .set_scale(20.)
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+        );
+        texts.insert(
+            "2_Page",
+            Text::new("")
+                .set_scale(15.)
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+        );
+        let mut messages_text = Text::new("")
+            .set_scale(13.)
+            .set_bounds(Vec2::new(300., 40.))
+            .set_wrap(true)
+            .to_owned();
+        match messages.len() {
+            0 => (),
+            1 => {
+                messages_text.add(TextFragment::new(messages[0].as_str()).color(Color::RED));
+            }
+            more => {
+                messages_text.add(
+                    TextFragment::new(format!("{} (and {} more)", messages[0], more - 1))
+                        .color(Color::RED),
+                );
+            }
+        }
+        texts.insert("3_Messages", messages_text);
+
+        let mut buttons = BTreeMap::new();
+        for (key, rect, label) in [
+            ("0_PrevPack", Rect::new(40., 90., 30., 30.), "<"),
+            ("1_NextPack", Rect::new(650., 90., 30., 30.), ">"),
+            ("2_Back", Rect::new(40., 420., 80., 30.), "Back"),
+            ("3_PrevPage", Rect::new(520., 420., 70., 30.), "Prev"),
+            ("4_NextPage", Rect::new(610., 420., 70., 30.), "Next"),
+        ] {
+            buttons.insert(
+                key,
+                Button::new(
+                    ctx,
+                    rect,
+                    Text::new(
+                        graphics::TextFragment::new(label)
+                            .color(Color::WHITE)
+                            .scale(18.),
+                    )
+                    .set_layout(graphics::TextLayout::center())
+                    .to_owned(),
+                ),
+            );
+        }
+
+        let vertices = [
+            graphics::Vertex {
+                position: [0., 0.],
+                uv: [0., 0.],
+                color: [0.001, 0., 0.001, 1.],
+            },
+            graphics::Vertex {
+                position: [SCREEN_SIZE.0, 0.],
+                uv: [SCREEN_SIZE.0, 0.],
+                color: [0., 0., 0.01, 1.],
+            },
+            graphics::Vertex {
+                position: [SCREEN_SIZE.0 / 2., SCREEN_SIZE.1 / 2.],
+                uv: [SCREEN_SIZE.0 / 2., SCREEN_SIZE.1 / 2.],
+                color: [0.015, 0., 0.02, 1.],
+            },
+            graphics::Vertex {
+                position: [SCREEN_SIZE.0, SCREEN_SIZE.1],
+                uv: [SCREEN_SIZE.0, SCREEN_SIZE.1],
+                color: [0.001, 0., 0.001, 1.],
+            },
+            graphics::Vertex {
+                position: [0., SCREEN_SIZE.1],
+                uv: [0., SCREEN_SIZE.1],
+                color: [0., 0., 0.01, 1.],
+            },
+        ];
+        let indices = [0, 1, 2, 2, 1, 3, 3, 2, 4, 4, 2, 0];
+        let background = graphics::Mesh::from_data(
+            ctx,
+            graphics::MeshData {
+                vertices: &vertices,
+                indices: &indices,
+            },
+        );
+
+        let mut library = PuzzleLibrary {
+            texts,
+            buttons,
+            rows: Vec::new(),
+            packs,
+            ratings,
+            completions,
+            pack_index: 0,
+            page: 0,
+            cursor: None,
+            background,
+            selected: None,
+            change_state: None,
+        };
+        library.layout_rows(ctx);
+        library
+    }
+
+    fn page_count(&self) -> usize {
+        self.packs
+            .get(self.pack_index)
+            .map_or(1, |pack| pack.puzzles.len().div_ceil(ROWS_PER_PAGE).max(1))
+    }
+
+    /// Rebuilds the pack title, page number and puzzle rows after moving around
+    fn layout_rows(&mut self, ctx: &Context) {
+        self.rows.clear();
+        let page_count = self.page_count();
+        let pack_text = self.texts.get_mut("1_Pack").unwrap();
+        pack_text.clear();
+        let Some(pack) = self.packs.get(self.pack_index) else {
+            pack_text.add("No puzzle packs in the puzzles folder");
+            self.texts.get_mut("2_Page").unwrap().clear();
+            return;
+        };
+        pack_text.add(format!("{} ({} puzzles)", pack.name, pack.puzzles.len()));
+        let page_text = self.texts.get_mut("2_Page").unwrap();
+        page_text.clear();
+        page_text.add(format!("Page {}/{}", self.page + 1, page_count));
+
+        let first = self.page * ROWS_PER_PAGE;
+        for (row, index) in (first..pack.puzzles.len().min(first + ROWS_PER_PAGE)).enumerate() {
+            let puzzle = &pack.puzzles[index];
+            let rating = &self.ratings[self.pack_index][index];
+            let (status, color) = match self.completions.get(&export::to_line(&puzzle.givens)) {
+                Some(best) => (
+                    format!("Solved, best {}", TimeUI::format_common(best)),
+                    Color::GREEN,
+                ),
+                None => ("Unsolved".to_string(), Color::WHITE),
+            };
+            self.rows.push(Button::new(
+                ctx,
+                Rect::new(40., 130. + row as f32 * 34., 640., 30.),
+                Text::new(
+                    TextFragment::new(format!(
+                        "#{}   {} ({})   {}",
+                        index + 1,
+                        rating.difficulty(),
+                        rating,
+                        status
+                    ))
+                    .color(color)
+                    .scale(16.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            ));
+        }
+        if let Some(row) = self.cursor.and_then(|cursor| cursor.checked_sub(first)) {
+            if let Some(rowui) = self.rows.get_mut(row) {
+                rowui.focused = true;
+            }
+        }
+    }
+
+    fn press(&mut self, ctx: &Context, key: &str) {
+        let pack_count = self.packs.len().max(1);
+        match key {
+            "0_PrevPack" => {
+                self.pack_index = (self.pack_index + pack_count - 1) % pack_count;
+                self.page = 0;
+            }
+            "1_NextPack" => {
+                self.pack_index = (self.pack_index + 1) % pack_count;
+                self.page = 0;
+            }
+            "2_Back" => self.change_state = Some(GameState::SelectDifficulty),
+            "3_PrevPage" => self.page = self.page.saturating_sub(1),
+            "4_NextPage" => self.page = (self.page + 1).min(self.page_count() - 1),
+            _ => return,
+        }
+        // Keep the keyboard cursor on the page being shown
+        self.cursor = self.cursor.map(|_| self.page * ROWS_PER_PAGE);
+        self.layout_rows(ctx);
+    }
+
+    fn open(&mut self, index: usize) {
+        self.selected = Some(self.packs[self.pack_index].puzzles[index]);
+        self.change_state = Some(GameState::Playing);
+    }
+}
+
+impl StateTrait for PuzzleLibrary {
+    fn update(
+        &mut self,
+        _ctx: &Context,
+        addon_ctx: &mut AddOnContext,
+    ) -> GameResult<Option<GameState>> {
+        if let Some(new_state) = self.change_state {
+            self.change_state = None;
+            addon_ctx.imported = self.selected.take();
+            return Ok(Some(new_state));
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+        canvas.draw(&self.background, graphics::DrawParam::default());
+
+        for (_key, button) in self.buttons.iter_mut() {
+            button.draw(canvas);
+        }
+        for row in self.rows.iter_mut() {
+            row.draw(canvas);
+        }
+
+        for (key, text) in self.texts.iter() {
+            match *key {
+                "0_Title" => canvas.draw(text, Vec2::new(360., 45.)),
+                "1_Pack" => canvas.draw(text, Vec2::new(360., 105.)),
+                "2_Page" => canvas.draw(text, Vec2::new(465., 435.)),
+                "3_Messages" => canvas.draw(text, Vec2::new(135., 420.)),
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn mouse_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: &MouseButton,
+        point: &Point2<f32>,
+    ) -> GameResult {
+        if *button != MouseButton::Left {
+            return Ok(());
+        }
+        let mut opened = None;
+        for (row, rowui) in self.rows.iter().enumerate() {
+            if rowui.rect.contains(*point) {
+                opened = Some(self.page * ROWS_PER_PAGE + row);
+            }
+        }
+        if let Some(index) = opened {
+            self.open(index);
+        }
+
+        let mut pressed = None;
+        for (key, buttonui) in self.buttons.iter() {
+            if buttonui.rect.contains(*point) {
+                pressed = Some(*key);
+            }
+        }
+        if let Some(key) = pressed {
+            self.press(ctx, key);
+        }
+        Ok(())
+    }
+
+    /// Up and Down pick a puzzle, Left and Right switch packs
+    fn key_down_event(&mut self, ctx: &mut Context, input: &KeyInput) -> GameResult {
+        let count = self
+            .packs
+            .get(self.pack_index)
+            .map_or(0, |pack| pack.puzzles.len());
+        match input.keycode {
+            Some(KeyCode::Escape) => self.press(ctx, "2_Back"),
+            Some(KeyCode::Left | KeyCode::A) => self.press(ctx, "0_PrevPack"),
+            Some(KeyCode::Right | KeyCode::D) => self.press(ctx, "1_NextPack"),
+            Some(KeyCode::Up | KeyCode::W | KeyCode::Down | KeyCode::S) if count > 0 => {
+                let up = matches!(input.keycode, Some(KeyCode::Up | KeyCode::W));
+                let cursor = match self.cursor {
+                    None => self.page * ROWS_PER_PAGE,
+                    Some(cursor) if up => cursor.saturating_sub(1),
+                    Some(cursor) => (cursor + 1).min(count - 1),
+                };
+                self.cursor = Some(cursor);
+                self.page = cursor / ROWS_PER_PAGE;
+                self.layout_rows(ctx);
+            }
+            Some(KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space) => {
+                if let Some(cursor) = self.cursor {
+                    self.open(cursor);
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
@@ -3,17 +3,32 @@ use std::collections::BTreeMap;
 use ggez::{
     glam::Vec2,
     graphics::{self, Color, Rect, Text},
+    input::keyboard::KeyCode,
     Context, GameResult,
 };
 
-use crate::game::{constants::*, entity::Difficulty, game_states::*, ui::*};
+use crate::game::{
+    constants::*,
+    entity::{Difficulty, Settings},
+    game_states::*,
+    saves,
+    ui::*,
+};
+use sudoku_core::{solver::SolverKind, PuzzleCode};
 
 pub struct SelectDifficulty {
     texts: BTreeMap<&'static str, Text>,
     buttons: BTreeMap<&'static str, Button>,
+    code_input: TextInput,
+    /// The None button, put away while Killer is picked. None is free play on an empty
+    /// grid, which Killer has no version of.
+    stashed_none: Option<Button>,
+    settings: Settings,
     background: graphics::Mesh,
     change_state: Option<GameState>,
     selected_difficulty: Option<Difficulty>,
+    selected_code: Option<PuzzleCode>,
+    invalid_code: bool,
 }
 
 impl SelectDifficulty {
@@ -39,6 +54,26 @@ impl SelectDifficulty {
             .set_layout(graphics::TextLayout::center())
             .to_owned(),
         );
+        texts.insert(
+            "2_Code",
+            Text::new(
+                graphics::TextFragment::new("Puzzle code")
+                    .color(Color::WHITE)
+                    .scale(15.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        texts.insert(
+            "3_InvalidCode",
+            Text::new(
+                graphics::TextFragment::new("Invalid code")
+                    .color(Color::RED)
+                    .scale(15.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
         let mut buttons = BTreeMap::new();
         buttons.insert(
             "0_None",
@@ -110,6 +145,63 @@ impl SelectDifficulty {
                 .to_owned(),
             ),
         );
+        buttons.insert(
+            "5_Code",
+            Button::new(
+                ctx,
+                Rect::new(480., 240., 140., 30.),
+                Text::new(
+                    graphics::TextFragment::new("Play Code")
+                        .color(Color::WHITE)
+                        .scale(18.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            ),
+        );
+        buttons.insert(
+            "6_Library",
+            Button::new(
+                ctx,
+                Rect::new(480., 320., 140., 30.),
+                Text::new(
+                    graphics::TextFragment::new("Library")
+                        .color(Color::WHITE)
+                        .scale(18.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            ),
+        );
+        let (settings, problem) = saves::load_settings();
+        buttons.insert(
+            "7_Challenge",
+            Button::new(
+                ctx,
+                Rect::new(480., 360., 140., 30.),
+                SelectDifficulty::challenge_text(settings.mistake_limit),
+            ),
+        );
+        buttons.insert(
+            "8_Type",
+            Button::new(
+                ctx,
+                Rect::new(480., 400., 140., 30.),
+                SelectDifficulty::type_text(settings.killer),
+            ),
+        );
+        buttons.insert(
+            "9_Solver",
+            Button::new(
+                ctx,
+                Rect::new(290., 400., 140., 30.),
+                SelectDifficulty::solver_text(settings.solver),
+            ),
+        );
+        if let Some(problem) = problem {
+            texts.insert("4_Problem", SelectDifficulty::problem_text(problem));
+        }
+        let code_input = TextInput::new(ctx, Rect::new(480., 200., 140., 30.), 11);
         let vertices = [
             graphics::Vertex {
                 position: [0., 0.],
@@ -145,12 +237,170 @@ impl SelectDifficulty {
                 indices: &indices,
             },
         );
+        let stashed_none = if settings.killer {
+            buttons.remove("0_None")
+        } else {
+            None
+        };
         SelectDifficulty {
             texts,
             buttons,
+            code_input,
+            stashed_none,
+            settings,
             background,
             change_state: None,
             selected_difficulty: None,
+            selected_code: None,
+            invalid_code: false,
+        }
+    }
+}
+
+impl SelectDifficulty {
+    fn challenge_text(mistake_limit: Option<u32>) -> Text {
+        let label = match mistake_limit {
+            Some(limit) => format!("Challenge: {}", limit),
+            None => String::from("Challenge: Off"),
+        };
+        Text::new(
+            graphics::TextFragment::new(label)
+                .color(Color::WHITE)
+                .scale(18.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    fn type_text(killer: bool) -> Text {
+        let label = if killer {
+            "Type: Killer"
+        } else {
+            "Type: Classic"
+        };
+        Text::new(
+            graphics::TextFragment::new(label)
+                .color(Color::WHITE)
+                .scale(18.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    fn solver_text(solver: SolverKind) -> Text {
+        let name = match solver {
+            SolverKind::Backtrack => "Backtrack",
+            SolverKind::Bitmask => "Bitmask",
+            SolverKind::DancingLinks => "DLX",
+        };
+        Text::new(
+            graphics::TextFragment::new(format!("Solver: {}", name))
+                .color(Color::WHITE)
+                .scale(16.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    fn problem_text(problem: String) -> Text {
+        Text::new(
+            graphics::TextFragment::new(problem)
+                .color(Color::RED)
+                .scale(15.),
+        )
+        .set_layout(graphics::TextLayout::center())
+        .to_owned()
+    }
+
+    /// Moves on to the next number of strikes allowed, remembering it for next time
+    fn cycle_challenge(&mut self) {
+        let index = MISTAKE_LIMITS
+            .iter()
+            .position(|limit| *limit == self.settings.mistake_limit)
+            .unwrap_or(0);
+        self.settings.mistake_limit = MISTAKE_LIMITS[(index + 1) % MISTAKE_LIMITS.len()];
+        if let Some(button) = self.buttons.get_mut("7_Challenge") {
+            button.text = SelectDifficulty::challenge_text(self.settings.mistake_limit);
+        }
+        self.store_settings();
+    }
+
+    /// Switches new games between classic and Killer sudoku, remembering it for next time
+    fn toggle_type(&mut self) {
+        self.settings.killer = !self.settings.killer;
+        if self.settings.killer {
+            self.stashed_none = self.buttons.remove("0_None");
+        } else if let Some(button) = self.stashed_none.take() {
+            self.buttons.insert("0_None", button);
+        }
+        if let Some(button) = self.buttons.get_mut("8_Type") {
+            button.text = SelectDifficulty::type_text(self.settings.killer);
+        }
+        self.store_settings();
+    }
+
+    /// Moves on to the next backend for generating classic puzzles and checking imports
+    fn cycle_solver(&mut self) {
+        // Backtracking takes seconds per Hard puzzle, it is only there for tests and
+        // benchmarks
+        self.settings.solver = match self.settings.solver {
+            SolverKind::Bitmask => SolverKind::DancingLinks,
+            SolverKind::Backtrack | SolverKind::DancingLinks => SolverKind::Bitmask,
+        };
+        if let Some(button) = self.buttons.get_mut("9_Solver") {
+            button.text = SelectDifficulty::solver_text(self.settings.solver);
+        }
+        self.store_settings();
+    }
+
+    fn store_settings(&mut self) {
+        match saves::store_settings(&self.settings) {
+            Ok(()) => {
+                self.texts.remove("4_Problem");
+            }
+            Err(e) => {
+                self.texts
+                    .insert("4_Problem", SelectDifficulty::problem_text(e.to_string()));
+            }
+        }
+    }
+
+    fn press(&mut self, key: &str) {
+        match key {
+            "0_None" => {
+                self.selected_difficulty = Some(Difficulty::None);
+                self.change_state = Some(GameState::Playing);
+            }
+            "1_Easy" => {
+                self.selected_difficulty = Some(Difficulty::Easy);
+                self.change_state = Some(GameState::Playing);
+            }
+            "2_Intermediate" => {
+                self.selected_difficulty = Some(Difficulty::Intermediate);
+                self.change_state = Some(GameState::Playing);
+            }
+            "3_Hard" => {
+                self.selected_difficulty = Some(Difficulty::Hard);
+                self.change_state = Some(GameState::Playing);
+            }
+            "4_Back" => {
+                self.change_state = Some(GameState::MainMenu);
+            }
+            // With nothing typed yet, pressing it starts typing instead
+            "5_Code" if self.code_input.value.is_empty() => self.code_input.focused = true,
+            "5_Code" => match self.code_input.value.parse::<PuzzleCode>() {
+                Ok(code) => {
+                    self.selected_difficulty = Some(code.difficulty);
+                    self.selected_code = Some(code);
+                    self.change_state = Some(GameState::Playing);
+                }
+                Err(_) => self.invalid_code = true,
+            },
+            "6_Library" => self.change_state = Some(GameState::PuzzleLibrary),
+            "7_Challenge" => self.cycle_challenge(),
+            "8_Type" => self.toggle_type(),
+            "9_Solver" => self.cycle_solver(),
+            _ => (),
         }
     }
 }
@@ -164,6 +414,8 @@ impl StateTrait for SelectDifficulty {
         if let Some(new_state) = self.change_state {
             self.change_state = None;
             addon_ctx.difficulty = self.selected_difficulty;
+            addon_ctx.puzzle_code = self.selected_code;
+            addon_ctx.daily = None;
             return Ok(Some(new_state));
         }
         Ok(None)
@@ -175,11 +427,15 @@ impl StateTrait for SelectDifficulty {
         for (_key, button) in self.buttons.iter_mut() {
             button.draw(canvas);
         }
+        self.code_input.draw(canvas);
 
         for (key, text) in self.texts.iter() {
             match *key {
                 "0_Title" => canvas.draw(text, Vec2::new(360., 100.)),
                 "1_Author" => canvas.draw(text, Vec2::new(640., 450.)),
+                "2_Code" => canvas.draw(text, Vec2::new(550., 185.)),
+                "3_InvalidCode" if self.invalid_code => canvas.draw(text, Vec2::new(550., 285.)),
+                "4_Problem" => canvas.draw(text, Vec2::new(360., 455.)),
                 _ => (),
             }
         }
@@ -193,32 +449,47 @@ impl StateTrait for SelectDifficulty {
         button: &MouseButton,
         point: &Point2<f32>,
     ) -> GameResult {
-        for (key, buttonui) in self.buttons.iter_mut() {
-            if buttonui.rect.contains(*point) && *button == MouseButton::Left {
-                match *key {
-                    "0_None" => {
-                        self.selected_difficulty = Some(Difficulty::None);
-                        self.change_state = Some(GameState::Playing);
-                    }
-                    "1_Easy" => {
-                        self.selected_difficulty = Some(Difficulty::Easy);
-                        self.change_state = Some(GameState::Playing);
-                    }
-                    "2_Intermediate" => {
-                        self.selected_difficulty = Some(Difficulty::Intermediate);
-                        self.change_state = Some(GameState::Playing);
-                    }
-                    "3_Hard" => {
-                        self.selected_difficulty = Some(Difficulty::Hard);
-                        self.change_state = Some(GameState::Playing);
-                    }
-                    "4_Back" => {
-                        self.change_state = Some(GameState::MainMenu);
-                    }
-                    _ => (),
+        if *button != MouseButton::Left {
+            return Ok(());
+        }
+        self.code_input.focused = self.code_input.rect.contains(*point);
+        let mut pressed = None;
+        for (key, buttonui) in self.buttons.iter() {
+            if buttonui.rect.contains(*point) {
+                pressed = Some(*key);
+            }
+        }
+        if let Some(key) = pressed {
+            self.press(key);
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: &KeyInput) -> GameResult {
+        if self.code_input.focused {
+            // Letters belong to the code while typing
+            match input.keycode {
+                Some(KeyCode::Return | KeyCode::NumpadEnter) => self.press("5_Code"),
+                Some(KeyCode::Back) => {
+                    self.code_input.delete();
+                    self.invalid_code = false;
                 }
+                Some(KeyCode::Escape | KeyCode::Tab) => self.code_input.focused = false,
+                _ => (),
             }
+            return Ok(());
+        }
+        if input.keycode == Some(KeyCode::Escape) {
+            self.press("4_Back");
+        } else if let Some(key) = navigate_buttons(&mut self.buttons, input) {
+            self.press(key);
         }
         Ok(())
     }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        self.code_input.input(character);
+        self.invalid_code = false;
+        Ok(())
+    }
 }
@@ -0,0 +1,309 @@
+use crate::game::{
+    constants::SCREEN_SIZE,
+    context::AddOnContext,
+    entity::{Difficulty, Records, Score},
+    game_states::*,
+    saves,
+    ui::*,
+};
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Mesh, Text},
+    input::keyboard::KeyCode,
+    Context, GameResult,
+};
+use std::{collections::BTreeMap, time::Duration};
+use sudoku_core::daily;
+
+/// Table rows, in the leaderboard's tab order
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Intermediate,
+    Difficulty::Hard,
+    Difficulty::None,
+];
+
+/// Column headers and the x each column is centered on
+const COLUMNS: [(&str, f32); 7] = [
+    ("Started", 210.),
+    ("Finished", 290.),
+    ("Rate", 365.),
+    ("Average", 440.),
+    ("Best", 520.),
+    ("Hints", 590.),
+    ("Mistakes", 660.),
+];
+
+pub struct Statistics {
+    texts: BTreeMap<&'static str, Text>,
+    /// Every cell of the table with where it goes
+    table: Vec<(Text, Vec2)>,
+    back_button: Button,
+    background: Mesh,
+    change_state: Option<GameState>,
+}
+
+impl Statistics {
+    pub fn new(ctx: &Context) -> Self {
+        let (records, problem) = saves::load_records();
+
+        let mut texts = BTreeMap::new();
+        texts.insert(
+            "0_Title",
+            Text::new(
+                graphics::TextFragment::new("STATISTICS")
+                    .color(graphics::Color::WHITE)
+                    .scale(50.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        texts.insert(
+            "1_Author",
+            Text::new(
+                graphics::TextFragment::new("Made by alimulap")
+                    .color(graphics::Color::WHITE)
+                    .scale(15.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        let days: Vec<u32> = records
+            .scores
+            .iter()
+            .filter_map(|score| score.daily)
+            .collect();
+        let streak = daily::streak(days.iter().copied(), daily::today());
+        let longest = daily::longest_streak(days);
+        texts.insert(
+            "2_Streak",
+            Text::new(
+                graphics::TextFragment::new(format!(
+                    "Daily streak: {}, longest: {}",
+                    days_text(streak),
+                    days_text(longest)
+                ))
+                .color(graphics::Color::WHITE)
+                .scale(18.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        texts.insert(
+            "3_Note",
+            Text::new(
+                graphics::TextFragment::new("Times, hints and mistakes are per finished game")
+                    .color(graphics::Color::new(0.7, 0.7, 0.7, 1.))
+                    .scale(14.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        if let Some(problem) = problem {
+            texts.insert(
+                "4_Problem",
+                Text::new(
+                    graphics::TextFragment::new(problem)
+                        .color(graphics::Color::RED)
+                        .scale(15.),
+                )
+                .set_layout(graphics::TextLayout::center())
+                .to_owned(),
+            );
+        }
+
+        let vertices = [
+            graphics::Vertex {
+                position: [0., 0.],
+                uv: [0., 0.],
+                color: [0.001, 0., 0.001, 1.],
+            },
+            graphics::Vertex {
+                position: [SCREEN_SIZE.0, 0.],
+                uv: [SCREEN_SIZE.0, 0.],
+                color: [0., 0., 0.01, 1.],
+            },
+            graphics::Vertex {
+                position: [SCREEN_SIZE.0 / 2., SCREEN_SIZE.1 / 2.],
+                uv: [SCREEN_SIZE.0 / 2., SCREEN_SIZE.1 / 2.],
+                color: [0.015, 0., 0.02, 1.],
+            },
+            graphics::Vertex {
+                position: [SCREEN_SIZE.0, SCREEN_SIZE.1],
+                uv: [SCREEN_SIZE.0, SCREEN_SIZE.1],
+                color: [0.001, 0., 0.001, 1.],
+            },
+            graphics::Vertex {
+                position: [0., SCREEN_SIZE.1],
+                uv: [0., SCREEN_SIZE.1],
+                color: [0., 0., 0.01, 1.],
+            },
+        ];
+        let indices = [0, 1, 2, 2, 1, 3, 3, 2, 4, 4, 2, 0];
+        let background = graphics::Mesh::from_data(
+            ctx,
+            graphics::MeshData {
+                vertices: &vertices,
+                indices: &indices,
+            },
+        );
+        let mut back_button = Button::new(
+            ctx,
+            graphics::Rect::new(30., 420., 80., 30.),
+            Text::new(
+                graphics::TextFragment::new("Back")
+                    .color(graphics::Color::WHITE)
+                    .scale(20.),
+            )
+            .set_layout(graphics::TextLayout::center())
+            .to_owned(),
+        );
+        // The only thing to press, so keyboard players start on it
+        back_button.focused = true;
+
+        Statistics {
+            texts,
+            table: Statistics::table(&records),
+            back_button,
+            background,
+            change_state: None,
+        }
+    }
+
+    /// Header, one row per difficulty and a total row
+    fn table(records: &Records) -> Vec<(Text, Vec2)> {
+        let cell = |text: String, color: graphics::Color| {
+            Text::new(graphics::TextFragment::new(text).color(color).scale(16.))
+                .set_layout(graphics::TextLayout::center())
+                .to_owned()
+        };
+        let mut table = Vec::new();
+        for (header, x) in COLUMNS {
+            table.push((
+                cell(String::from(header), graphics::Color::YELLOW),
+                Vec2::new(x, 110.),
+            ));
+        }
+
+        let mut rows: Vec<(String, u32, Vec<&Score>)> = DIFFICULTIES
+            .iter()
+            .map(|difficulty| {
+                (
+                    difficulty.to_string(),
+                    records.started.get(difficulty).copied().unwrap_or(0),
+                    records
+                        .scores
+                        .iter()
+                        .filter(|score| score.difficulty == *difficulty)
+                        .collect(),
+                )
+            })
+            .collect();
+        rows.push((
+            String::from("Total"),
+            records.started.values().sum(),
+            records.scores.iter().collect(),
+        ));
+
+        for (index, (label, started, scores)) in rows.into_iter().enumerate() {
+            let y = 145. + index as f32 * 30.;
+            let color = if index == DIFFICULTIES.len() {
+                graphics::Color::YELLOW
+            } else {
+                graphics::Color::WHITE
+            };
+            table.push((
+                Text::new(graphics::TextFragment::new(label).color(color).scale(16.)),
+                Vec2::new(40., y - 8.),
+            ));
+            let finished = scores.len() as u32;
+            let times: Vec<Duration> = scores.iter().map(|score| score.time).collect();
+            let per_game = |total: u32| match finished {
+                0 => String::from("-"),
+                _ => format!("{:.1}", total as f32 / finished as f32),
+            };
+            let values = [
+                started.to_string(),
+                finished.to_string(),
+                match started {
+                    0 => String::from("-"),
+                    // Old files may have more finished games than started ones
+                    _ => format!("{}%", (finished * 100 / started).min(100)),
+                },
+                match finished {
+                    0 => String::from("-"),
+                    _ => TimeUI::format_common(&(times.iter().sum::<Duration>() / finished)),
+                },
+                times
+                    .iter()
+                    .min()
+                    .map_or(String::from("-"), TimeUI::format_common),
+                per_game(scores.iter().map(|score| score.hints).sum()),
+                per_game(scores.iter().map(|score| score.mistakes).sum()),
+            ];
+            for (value, (_, x)) in values.into_iter().zip(COLUMNS) {
+                table.push((cell(value, color), Vec2::new(x, y)));
+            }
+        }
+        table
+    }
+}
+
+fn days_text(days: u32) -> String {
+    if days == 1 {
+        String::from("1 day")
+    } else {
+        format!("{} days", days)
+    }
+}
+
+impl StateTrait for Statistics {
+    fn update(
+        &mut self,
+        _ctx: &Context,
+        _addon_ctx: &mut AddOnContext,
+    ) -> GameResult<Option<GameState>> {
+        if let Some(new_state) = self.change_state {
+            return Ok(Some(new_state));
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+        canvas.draw(&self.background, graphics::DrawParam::default());
+        for (key, text) in self.texts.iter() {
+            match *key {
+                "0_Title" => canvas.draw(text, Vec2::new(360., 45.)),
+                "1_Author" => canvas.draw(text, Vec2::new(640., 450.)),
+                "2_Streak" => canvas.draw(text, Vec2::new(360., 330.)),
+                "3_Note" => canvas.draw(text, Vec2::new(360., 360.)),
+                "4_Problem" => canvas.draw(text, Vec2::new(360., 465.)),
+                _ => (),
+            }
+        }
+        for (text, position) in self.table.iter() {
+            canvas.draw(text, *position);
+        }
+        self.back_button.draw(canvas);
+        Ok(())
+    }
+
+    fn mouse_button_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: &MouseButton,
+        point: &Point2<f32>,
+    ) -> GameResult {
+        if *button == MouseButton::Left && self.back_button.rect.contains(*point) {
+            self.change_state = Some(GameState::MainMenu);
+        }
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, _ctx: &mut Context, input: &KeyInput) -> GameResult {
+        if let Some(KeyCode::Escape | KeyCode::Return | KeyCode::NumpadEnter) = input.keycode {
+            self.change_state = Some(GameState::MainMenu);
+        }
+        Ok(())
+    }
+}
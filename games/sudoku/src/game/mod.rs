@@ -3,4 +3,5 @@ mod context;
 pub mod core;
 mod entity;
 mod game_states;
+mod saves;
 mod ui;
@@ -0,0 +1,349 @@
+//! Everything the game keeps on disk.
+//!
+//! Files live in ggez's user data directory. Writes go to a temporary file that is renamed
+//! over the old one, and the previous good copy is kept as a `.bak` to fall back on when a
+//! file turns out to be corrupt. Loading never fails: problems come back as a message to
+//! show the player, next to a usable default.
+
+use ggez::{GameError, GameResult};
+use ron::{
+    de,
+    error::{Position, SpannedError, SpannedResult},
+    ser,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+use sudoku_core::{export, import, solver::Solver, Grid, PuzzleCode};
+
+use super::entity::{Difficulty, Pack, Records, SavedGame, Score, Settings, RECORDS_VERSION};
+
+const SCORES_FILE: &str = "scores.ron";
+const GAME_FILE: &str = "game.ron";
+const SETTINGS_FILE: &str = "settings.ron";
+const COMPLETIONS_FILE: &str = "library.ron";
+/// Where saves were kept before they moved to the data directory
+const LEGACY_SAVES_DIR: &str = "saves";
+/// Puzzle collections shipped with the game
+const PUZZLES_DIR: &str = "puzzles";
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Picks the directory to save into, creating it and bringing over saves from the old
+/// location the first time. Runs once before any state is made.
+pub fn init(data_dir: &Path) -> GameResult {
+    let data_dir = DATA_DIR.get_or_init(|| data_dir.to_path_buf());
+    fs::create_dir_all(data_dir)?;
+    let legacy_dir = game_dir(LEGACY_SAVES_DIR);
+    for name in [SCORES_FILE, GAME_FILE, SETTINGS_FILE, COMPLETIONS_FILE] {
+        let legacy = legacy_dir.join(name);
+        let path = data_dir.join(name);
+        if legacy.is_file() && !path.exists() {
+            fs::copy(legacy, path)?;
+        }
+    }
+    Ok(())
+}
+
+/// A folder shipped with the game. Under `cargo run` that's the crate directory, otherwise
+/// the one holding the executable.
+fn game_dir(name: &str) -> PathBuf {
+    let base = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        })
+        .unwrap_or_default();
+    base.join(name)
+}
+
+fn data_dir() -> &'static Path {
+    DATA_DIR
+        .get()
+        .expect("saves::init runs before anything is saved")
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("ron.bak")
+}
+
+/// Reads a file, falling back to its backup when it's corrupt. None when there is
+/// nothing usable. The corrupt file is moved aside rather than deleted.
+fn load_optional<T: DeserializeOwned>(name: &str) -> (Option<T>, Option<String>) {
+    load_optional_with(name, |serialized| de::from_str(serialized))
+}
+
+/// `load_optional` for files with more than one layout, `parse` reads any of them
+fn load_optional_with<T>(
+    name: &str,
+    parse: impl Fn(&str) -> SpannedResult<T>,
+) -> (Option<T>, Option<String>) {
+    let path = data_dir().join(name);
+    let serialized = match fs::read_to_string(&path) {
+        Ok(serialized) => serialized,
+        Err(e) if e.kind() == ErrorKind::NotFound => return (None, None),
+        Err(e) => return (None, Some(format!("Couldn't read {}: {}", name, e))),
+    };
+    let error = match parse(&serialized) {
+        Ok(value) => return (Some(value), None),
+        Err(e) => e,
+    };
+    let corrupt = path.with_extension("ron.corrupt");
+    let moved = fs::rename(&path, &corrupt).is_ok();
+    let backup = backup_path(&path);
+    let restored = fs::read_to_string(&backup)
+        .ok()
+        .and_then(|serialized| parse(&serialized).ok());
+    let problem = match (&restored, moved) {
+        (Some(_), _) => {
+            let _ = fs::copy(&backup, &path);
+            format!("{} was damaged ({}), restored the backup", name, error)
+        }
+        (None, true) => format!(
+            "{} was damaged ({}), moved it to {}",
+            name,
+            error,
+            corrupt.display()
+        ),
+        (None, false) => format!("{} was damaged ({})", name, error),
+    };
+    (restored, Some(problem))
+}
+
+/// Like `load_optional`, with the default standing in for a missing file
+fn load<T: DeserializeOwned + Default>(name: &str) -> (T, Option<String>) {
+    let (value, problem) = load_optional(name);
+    (value.unwrap_or_default(), problem)
+}
+
+/// Replaces a file in one step, so a crash mid-write can't leave half a file behind
+fn store<T: Serialize>(name: &str, value: &T) -> GameResult {
+    let serialized = ser::to_string_pretty(value, ser::PrettyConfig::default().struct_names(true))
+        .map_err(|e| GameError::CustomError(e.to_string()))?;
+    let path = data_dir().join(name);
+    let temporary = path.with_extension("ron.tmp");
+    fs::write(&temporary, serialized)?;
+    // Only a file that still parses is worth keeping as the backup
+    let current_is_valid = fs::read_to_string(&path)
+        .ok()
+        .is_some_and(|current| de::from_str::<ron::Value>(&current).is_ok());
+    if current_is_valid {
+        fs::copy(&path, backup_path(&path))?;
+    }
+    fs::rename(&temporary, &path)?;
+    Ok(())
+}
+
+/// Just the version of `scores.ron`, readable whatever else a newer layout holds
+#[derive(Deserialize)]
+#[serde(rename = "Records")]
+struct RecordsHeader {
+    version: u32,
+}
+
+/// Version of a `scores.ron` written by a newer release of the game. Such a file is
+/// left alone, neither moved aside as damaged nor written back in the older layout.
+fn newer_records_version() -> Option<u32> {
+    let serialized = fs::read_to_string(data_dir().join(SCORES_FILE)).ok()?;
+    let header: RecordsHeader = de::from_str(&serialized).ok()?;
+    (header.version > RECORDS_VERSION).then_some(header.version)
+}
+
+fn newer_records_problem(version: u32) -> String {
+    format!(
+        "{} is from a newer version of the game (layout {}), scores aren't saved",
+        SCORES_FILE, version
+    )
+}
+
+/// Reads the current layout of `scores.ron`, or upgrades a version 1 list of scores.
+/// Versions newer than `RECORDS_VERSION` are refused.
+fn parse_records(serialized: &str) -> SpannedResult<Records> {
+    let records: Records = de::from_str(serialized).or_else(|e| {
+        de::from_str::<Vec<Score>>(serialized)
+            .map(Records::from_scores)
+            .map_err(|_| e)
+    })?;
+    if records.version > RECORDS_VERSION {
+        return Err(SpannedError {
+            code: ron::Error::Message(newer_records_problem(records.version)),
+            position: Position { line: 1, col: 1 },
+        });
+    }
+    Ok(records)
+}
+
+pub fn load_records() -> (Records, Option<String>) {
+    if let Some(version) = newer_records_version() {
+        return (Records::default(), Some(newer_records_problem(version)));
+    }
+    let (records, problem) = load_optional_with(SCORES_FILE, parse_records);
+    (records.unwrap_or_default(), problem)
+}
+
+/// Loads the records to change and write back, unless a newer game owns the file
+fn load_records_to_update() -> GameResult<Records> {
+    if let Some(version) = newer_records_version() {
+        return Err(GameError::CustomError(newer_records_problem(version)));
+    }
+    Ok(load_records().0)
+}
+
+/// Adds a score to the ones on disk
+pub fn record_score(score: Score) -> GameResult {
+    let mut records = load_records_to_update()?;
+    records.scores.push(score);
+    store(SCORES_FILE, &records)
+}
+
+/// Counts a new game toward the completion rate
+pub fn record_start(difficulty: Difficulty) -> GameResult {
+    let mut records = load_records_to_update()?;
+    *records.started.entry(difficulty).or_insert(0) += 1;
+    store(SCORES_FILE, &records)
+}
+
+/// Defaults when there are no settings yet
+pub fn load_settings() -> (Settings, Option<String>) {
+    load(SETTINGS_FILE)
+}
+
+pub fn store_settings(settings: &Settings) -> GameResult {
+    store(SETTINGS_FILE, settings)
+}
+
+/// The game the player left unfinished, if any
+pub fn load_game() -> (Option<SavedGame>, Option<String>) {
+    load_optional(GAME_FILE)
+}
+
+pub fn store_game(game: &SavedGame) -> GameResult {
+    store(GAME_FILE, game)
+}
+
+/// Removes the saved game once its puzzle is over. A save of some other puzzle,
+/// left for Continue while this one was played, is kept.
+pub fn delete_game(puzzle_code: Option<PuzzleCode>, givens: &Grid) -> GameResult {
+    let (saved, _) = load_game();
+    if !saved.is_some_and(|saved| saved.puzzle_code == puzzle_code && saved.givens == *givens) {
+        return Ok(());
+    }
+    match fs::remove_file(data_dir().join(GAME_FILE)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Writes each file name and contents into the exports folder, returning the folder
+pub fn store_export(files: &[(String, String)]) -> GameResult<PathBuf> {
+    let dir = data_dir().join("exports");
+    fs::create_dir_all(&dir)?;
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents)?;
+    }
+    Ok(dir)
+}
+
+/// Puzzle packs, one per file in the bundled puzzles folder and then the one in the
+/// data directory, each in name order.
+/// Files that fail to import and puzzles without a solution are described in the messages.
+pub fn load_packs(solver: &dyn Solver) -> (Vec<Pack>, Vec<String>) {
+    let mut packs = Vec::new();
+    let mut messages = Vec::new();
+    let mut paths = Vec::new();
+    let bundled = game_dir(PUZZLES_DIR);
+    for dir in [bundled.clone(), data_dir().join(PUZZLES_DIR)] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "txt" || ext == "sdk" || ext == "sdm")
+            })
+            .collect();
+        found.sort();
+        paths.extend(found);
+    }
+    if paths.is_empty() {
+        messages.push(format!(
+            "No puzzle files in {} or {}",
+            bundled.display(),
+            data_dir().join(PUZZLES_DIR).display()
+        ));
+    }
+    for path in paths {
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let imported = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| import::import_puzzles(&text, solver).map_err(|e| e.to_string()));
+        match imported {
+            Ok(imported) => {
+                let mut puzzles = Vec::new();
+                for puzzle in imported {
+                    if puzzle.solution.is_some() {
+                        puzzles.push(puzzle);
+                    } else if let Some(warning) = puzzle.warning() {
+                        messages.push(format!("{} {}", file_name, warning));
+                    }
+                }
+                if !puzzles.is_empty() {
+                    let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                    packs.push(Pack { name, puzzles });
+                }
+            }
+            Err(e) => messages.push(format!("{} {}", file_name, e)),
+        }
+    }
+    (packs, messages)
+}
+
+/// Best times of solved library puzzles, keyed by their givens as one line
+pub fn load_completions() -> (BTreeMap<String, Duration>, Option<String>) {
+    load(COMPLETIONS_FILE)
+}
+
+/// Remembers a solve, keeping the faster time if the puzzle was solved before
+pub fn record_completion(givens: &Grid, time: Duration) -> GameResult {
+    let (mut completions, _) = load_completions();
+    let best = completions.entry(export::to_line(givens)).or_insert(time);
+    *best = time.min(*best);
+    store(COMPLETIONS_FILE, &completions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_version_1_scores() {
+        let records = parse_records(include_str!("../../saves/scores.ron")).unwrap();
+        assert_eq!(records.version, RECORDS_VERSION);
+        assert!(!records.scores.is_empty());
+        let started: u32 = records.started.values().sum();
+        assert_eq!(started as usize, records.scores.len());
+    }
+
+    #[test]
+    fn refuses_newer_records() {
+        let newer = format!(
+            "Records(version: {}, started: {{}}, scores: [])",
+            RECORDS_VERSION + 1
+        );
+        assert!(parse_records(&newer).is_err());
+        let current = format!(
+            "Records(version: {}, started: {{}}, scores: [])",
+            RECORDS_VERSION
+        );
+        assert!(parse_records(&current).is_ok());
+    }
+}
@@ -1,25 +1,57 @@
 use ggez::{
     glam::Vec2,
-    graphics::{self, Canvas, DrawParam, Mesh, Rect, Text, TextFragment},
-    timer::TimeContext,
+    graphics::{self, Canvas, DrawParam, Mesh, Rect, Text, TextFragment, ZIndex},
+    input::keyboard::{KeyCode, KeyInput, KeyMods},
     Context,
 };
 
-use std::time::Duration;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 
+/// Stopwatch of the time spent solving, it only runs while not paused
 pub struct TimeUI {
-    pub time: TimeContext,
+    /// When the clock last started, None while paused
+    running_since: Option<Instant>,
+    /// Time counted before `running_since`, including earlier sessions of resumed games
+    offset: Duration,
     mesh: Text,
 }
 
 impl TimeUI {
     pub fn new() -> Self {
-        let time = TimeContext::new();
+        TimeUI::with_offset(Duration::ZERO)
+    }
+
+    pub fn with_offset(offset: Duration) -> Self {
         let mesh = Text::new("Time: ")
-            .add(TimeUI::format_common(&time.time_since_start()))
+            .add(TimeUI::format_common(&offset))
             .set_scale(20.)
             .to_owned();
-        TimeUI { time, mesh }
+        TimeUI {
+            running_since: Some(Instant::now()),
+            offset,
+            mesh,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.offset
+            + self
+                .running_since
+                .map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    pub fn pause(&mut self) {
+        self.offset = self.elapsed();
+        self.running_since = None;
+    }
+
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
     }
 
     pub fn format_common(time: &Duration) -> String {
@@ -30,8 +62,7 @@ impl TimeUI {
     }
 
     pub fn update(&mut self) {
-        self.mesh.fragments_mut()[1] =
-            TextFragment::new(TimeUI::format_common(&self.time.time_since_start()));
+        self.mesh.fragments_mut()[1] = TextFragment::new(TimeUI::format_common(&self.elapsed()));
     }
 
     pub fn draw(&mut self, canvas: &mut Canvas) {
@@ -43,6 +74,8 @@ pub struct Button {
     pub rect: Rect,
     pub mesh: Mesh,
     pub text: Text,
+    /// Picked with the keyboard, drawn highlighted
+    pub focused: bool,
 }
 
 impl Button {
@@ -59,16 +92,140 @@ impl Button {
         )
         .unwrap();
 
-        Button { rect, mesh, text }
+        Button {
+            rect,
+            mesh,
+            text,
+            focused: false,
+        }
     }
     pub fn draw(&mut self, canvas: &mut Canvas) {
-        canvas.draw(&self.mesh, DrawParam::default());
+        self.draw_z(canvas, 0);
+    }
+
+    /// Draws at a z index, for buttons on panels that cover the board
+    pub fn draw_z(&mut self, canvas: &mut Canvas, z: ZIndex) {
+        let color = if self.focused {
+            graphics::Color::YELLOW
+        } else {
+            graphics::Color::WHITE
+        };
+        canvas.draw(&self.mesh, DrawParam::default().color(color).z(z));
         canvas.draw(
             &self.text,
-            Vec2::new(
-                self.rect.x + self.rect.w / 2.,
-                self.rect.y + self.rect.h / 2.,
+            DrawParam::default()
+                .dest(Vec2::new(
+                    self.rect.x + self.rect.w / 2.,
+                    self.rect.y + self.rect.h / 2.,
+                ))
+                .z(z),
+        );
+    }
+}
+
+/// Keyboard navigation through menu buttons in key order. Up/Down, W/S and Tab move the
+/// focus, and Enter or Space gives back the key of the focused button to press.
+pub fn navigate_buttons(
+    buttons: &mut BTreeMap<&'static str, Button>,
+    input: &KeyInput,
+) -> Option<&'static str> {
+    let keys: Vec<&'static str> = buttons.keys().copied().collect();
+    let focused = keys.iter().position(|key| buttons[key].focused);
+    let step: isize = match input.keycode? {
+        KeyCode::Tab if input.mods.contains(KeyMods::SHIFT) => -1,
+        KeyCode::Up | KeyCode::W | KeyCode::Left | KeyCode::A => -1,
+        KeyCode::Down | KeyCode::S | KeyCode::Right | KeyCode::D | KeyCode::Tab => 1,
+        KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => {
+            return focused.map(|index| keys[index]);
+        }
+        _ => return None,
+    };
+    let next = match focused {
+        Some(index) => (index as isize + step).rem_euclid(keys.len() as isize) as usize,
+        None => 0,
+    };
+    for (index, key) in keys.iter().enumerate() {
+        buttons.get_mut(key).unwrap().focused = index == next;
+    }
+    None
+}
+
+pub struct TextInput {
+    pub rect: Rect,
+    pub value: String,
+    pub focused: bool,
+    max_len: usize,
+    mesh: Mesh,
+}
+
+impl TextInput {
+    pub fn new(ctx: &Context, rect: Rect, max_len: usize) -> Self {
+        let mesh = Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::Stroke(
+                graphics::StrokeOptions::default()
+                    .with_line_width(1.)
+                    .with_line_join(graphics::LineJoin::Bevel),
             ),
+            Rect::new(0., 0., rect.w, rect.h),
+            graphics::Color::WHITE,
+        )
+        .unwrap();
+
+        TextInput {
+            rect,
+            value: String::new(),
+            focused: false,
+            max_len,
+            mesh,
+        }
+    }
+
+    /// Handles a character from `text_input_event`, control characters are left out
+    pub fn input(&mut self, character: char) {
+        if self.focused && !character.is_control() && self.value.chars().count() < self.max_len {
+            self.value.push(character);
+        }
+    }
+
+    /// Removes the last character, for `KeyCode::Back` in `key_down_event`
+    pub fn delete(&mut self) {
+        if self.focused {
+            self.value.pop();
+        }
+    }
+
+    pub fn draw(&mut self, canvas: &mut Canvas) {
+        self.draw_z(canvas, 0);
+    }
+
+    /// Draws at a z index, for inputs on panels that cover the board
+    pub fn draw_z(&mut self, canvas: &mut Canvas, z: ZIndex) {
+        let color = if self.focused {
+            graphics::Color::CYAN
+        } else {
+            graphics::Color::WHITE
+        };
+        canvas.draw(
+            &self.mesh,
+            DrawParam::default()
+                .dest(Vec2::new(self.rect.x, self.rect.y))
+                .color(color)
+                .z(z),
+        );
+        let mut text = Text::new(TextFragment::new(self.value.as_str()).scale(18.));
+        if self.focused {
+            text.add(TextFragment::new("_").scale(18.));
+        }
+        canvas.draw(
+            text.set_layout(graphics::TextLayout {
+                h_align: graphics::TextAlign::Begin,
+                v_align: graphics::TextAlign::Middle,
+            }),
+            DrawParam::default()
+                .dest(Vec2::new(self.rect.x + 8., self.rect.y + self.rect.h / 2.))
+                .color(color)
+                .z(z),
         );
     }
 }